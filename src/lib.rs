@@ -1,2 +1,12 @@
+#![allow(clippy::module_inception)]
+
 mod simboli_thread;
 pub use simboli_thread::*;
+
+#[cfg(doctest)]
+#[doc = include_str!("../README.md")]
+struct ReadmeDoctests;
+
+#[cfg(doctest)]
+#[doc = include_str!("../dependencies_feature.md")]
+struct DependenciesFeatureDoctests;
@@ -0,0 +1,123 @@
+use std::marker::PhantomData;
+
+use crate::{
+    IdleStrategy, OutputTrait, SimboliThread, TaskOnceTrait, TaskWithDependenciesOnceTrait,
+    simboli_thread::thread_pool_core::{GroupTopology, PoolConfig},
+};
+
+/// thread pool configuration, the values are read at runtime
+pub struct SimboliThreadBuilder<F, FD, O>
+where
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    config: PoolConfig,
+    _marker: PhantomData<(F, FD, O)>,
+}
+
+impl<F, FD, O> SimboliThreadBuilder<F, FD, O>
+where
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    /// default, one thread per available core, queue capacity 64, group size 2, harvest every 32 rounds
+    pub fn new() -> SimboliThreadBuilder<F, FD, O> {
+        Self {
+            config: PoolConfig::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// number of threads in the thread pool
+    pub fn threads(mut self, threads: usize) -> SimboliThreadBuilder<F, FD, O> {
+        assert!(threads > 0, "thread pool needs at least one thread");
+        self.config.threads = threads;
+        self
+    }
+
+    /// number of tasks each thread takes from the primary list at once
+    pub fn queue_capacity(mut self, queue_capacity: usize) -> SimboliThreadBuilder<F, FD, O> {
+        assert!(
+            queue_capacity > 0,
+            "queue capacity must be greater than zero"
+        );
+        self.config.queue_capacity = queue_capacity;
+        self
+    }
+
+    /// number of threads sharing one harvesting group, the last group gets the threads left over
+    /// and is smaller when the number of threads is not a multiple of `group_size`.
+    /// a smaller group releases tasks waiting on dependencies sooner, a bigger group harvests less often
+    pub fn group_size(mut self, group_size: usize) -> SimboliThreadBuilder<F, FD, O> {
+        assert!(group_size > 0, "group size must be greater than zero");
+        self.config.groups = GroupTopology::Size(group_size);
+        self
+    }
+
+    /// one harvesting group for each cluster, `clusters` is the number of threads in each cluster.
+    /// the number of threads becomes the total of all clusters
+    pub fn clusters(
+        mut self,
+        clusters: impl IntoIterator<Item = usize>,
+    ) -> SimboliThreadBuilder<F, FD, O> {
+        let clusters: Vec<usize> = clusters.into_iter().collect();
+        assert!(
+            !clusters.is_empty(),
+            "thread pool needs at least one cluster"
+        );
+        assert!(
+            !clusters.contains(&0),
+            "cluster size must be greater than zero"
+        );
+        self.config.threads = clusters.iter().sum();
+        self.config.groups = GroupTopology::Clusters(clusters);
+        self
+    }
+
+    /// number of worker rounds between two harvests of the local waiting lists
+    pub fn harvest_interval(mut self, harvest_interval: usize) -> SimboliThreadBuilder<F, FD, O> {
+        self.config.harvest_interval = harvest_interval;
+        self
+    }
+
+    /// name the threads `{thread_name}-{id}`
+    pub fn thread_name(mut self, thread_name: impl Into<String>) -> SimboliThreadBuilder<F, FD, O> {
+        self.config.thread_name = Some(thread_name.into());
+        self
+    }
+
+    /// how idle workers wait for new task
+    pub fn idle_strategy(mut self, idle_strategy: IdleStrategy) -> SimboliThreadBuilder<F, FD, O> {
+        self.config.idle_strategy = idle_strategy;
+        self
+    }
+
+    /// keep track of pending tasks for `SimboliThread::graph_snapshot`, off by default.
+    /// each spawn takes a lock while it is on
+    pub fn graph_tracing(mut self, graph_tracing: bool) -> SimboliThreadBuilder<F, FD, O> {
+        self.config.graph_tracing = graph_tracing;
+        self
+    }
+
+    /// spawn the threads, panics if the clusters do not match the number of threads
+    pub fn build(self) -> SimboliThread<F, FD, O> {
+        if let Err(message) = self.config.validate() {
+            panic!("{}", message);
+        }
+
+        SimboliThread::init_with_config(self.config)
+    }
+}
+
+impl<F, FD, O> Default for SimboliThreadBuilder<F, FD, O>
+where
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    AnyOutput, ClosureTask, PeriodicHandle, PeriodicMode, Priority, SimboliThread,
+    TaskDependencies, Waiting,
+};
+
+/// thread pool running closures, no task type needed
+pub type ClosureThread<O> = SimboliThread<ClosureTask<O>, ClosureTask<O>, O>;
+
+/// thread pool running closures, each closure has its own output type
+pub type AnyThread = ClosureThread<AnyOutput>;
+
+/// `Waiting` handles of tasks with their own output types, a tuple of up to 6 `&Waiting<T>`.
+/// the outputs are `Sync`, the task reads them while the handles can too.
+/// see `AnyThread::spawn_typed_after`
+pub trait TypedDependencies {
+    /// references to the outputs, in the order of the handles
+    type Outputs<'a>;
+
+    /// type erased views of the handles
+    fn erased(&self) -> Vec<&Waiting<AnyOutput>>;
+
+    /// outputs of finished dependencies, panics if one did not return the type of its handle
+    fn outputs(dependencies: &[Waiting<AnyOutput>]) -> Self::Outputs<'_>;
+}
+
+macro_rules! typed_dependencies {
+    ($($T:ident $index:tt),+) => {
+        impl<'w, $($T),+> TypedDependencies for ($(&'w Waiting<$T>,)+)
+        where
+            $($T: Sync + 'static),+
+        {
+            type Outputs<'a> = ($(&'a $T,)+);
+
+            fn erased(&self) -> Vec<&Waiting<AnyOutput>> {
+                vec![$(self.$index.downcast_ref()),+]
+            }
+
+            fn outputs(dependencies: &[Waiting<AnyOutput>]) -> Self::Outputs<'_> {
+                ($(dependencies[$index]
+                    .downcast_ref::<$T>()
+                    .get()
+                    .expect("dependency did not return the type of its Waiting"),)+)
+            }
+        }
+    };
+}
+
+typed_dependencies!(A 0);
+typed_dependencies!(A 0, B 1);
+typed_dependencies!(A 0, B 1, C 2);
+typed_dependencies!(A 0, B 1, C 2, D 3);
+typed_dependencies!(A 0, B 1, C 2, D 3, E 4);
+typed_dependencies!(A 0, B 1, C 2, D 3, E 4, G 5);
+
+impl<O> SimboliThread<ClosureTask<O>, ClosureTask<O>, O>
+where
+    O: 'static + Send,
+{
+    pub fn closures() -> SimboliThread<ClosureTask<O>, ClosureTask<O>, O> {
+        Self::init()
+    }
+
+    pub fn spawn<T>(&self, f: T) -> Waiting<O>
+    where
+        T: FnOnce() -> O + Send + 'static,
+    {
+        self.spawn_task(ClosureTask::new(f))
+    }
+
+    pub fn spawn_with_priority<T>(&self, f: T, priority: Priority) -> Waiting<O>
+    where
+        T: FnOnce() -> O + Send + 'static,
+    {
+        self.spawn_task_with_priority(ClosureTask::new(f), priority)
+    }
+
+    pub fn spawn_after_delay<T>(&self, delay: Duration, f: T) -> Waiting<O>
+    where
+        T: FnOnce() -> O + Send + 'static,
+    {
+        self.spawn_task_after_delay(delay, ClosureTask::new(f))
+    }
+
+    pub fn spawn_at<T>(&self, deadline: Instant, f: T) -> Waiting<O>
+    where
+        T: FnOnce() -> O + Send + 'static,
+    {
+        self.spawn_task_at(deadline, ClosureTask::new(f))
+    }
+
+    /// fixed rate, late runs are skipped. see `spawn_periodic_with_mode`
+    pub fn spawn_periodic<T>(&self, interval: Duration, f: T) -> PeriodicHandle
+    where
+        T: Fn() -> O + Send + Sync + 'static,
+    {
+        self.spawn_periodic_with_mode(interval, PeriodicMode::default(), f)
+    }
+
+    pub fn spawn_periodic_with_mode<T>(
+        &self,
+        interval: Duration,
+        mode: PeriodicMode,
+        f: T,
+    ) -> PeriodicHandle
+    where
+        T: Fn() -> O + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        self.spawn_task_periodic(interval, mode, move || {
+            let f = f.clone();
+            ClosureTask::new(move || f())
+        })
+    }
+
+    /// dependencies from an iterator of closures
+    pub fn spawn_dependencies<I, T>(
+        &self,
+        dependencies: I,
+    ) -> TaskDependencies<ClosureTask<O>, ClosureTask<O>, O>
+    where
+        I: IntoIterator<Item = T>,
+        T: FnOnce() -> O + Send + 'static,
+    {
+        self.spawn_task_dependencies_iter(dependencies.into_iter().map(ClosureTask::new))
+    }
+
+    /// dependencies from an iterator of closures, each closure runs after `with_dependencies` are done
+    pub fn spawn_dependencies_with_dependencies<I, T>(
+        &self,
+        dependencies: I,
+        with_dependencies: &TaskDependencies<ClosureTask<O>, ClosureTask<O>, O>,
+    ) -> TaskDependencies<ClosureTask<O>, ClosureTask<O>, O>
+    where
+        I: IntoIterator<Item = T>,
+        O: Sync,
+        T: FnOnce(&[Waiting<O>]) -> O + Send + 'static,
+    {
+        self.spawn_task_dependencies_with_dependencies_iter(
+            dependencies.into_iter().map(ClosureTask::with_dependencies),
+            with_dependencies,
+        )
+    }
+
+    pub fn spawn_with_dependencies<T>(
+        &self,
+        f: T,
+        dependencies: &TaskDependencies<ClosureTask<O>, ClosureTask<O>, O>,
+    ) -> Waiting<O>
+    where
+        O: Sync,
+        T: FnOnce(&[Waiting<O>]) -> O + Send + 'static,
+    {
+        self.spawn_task_with_dependencies(ClosureTask::with_dependencies(f), dependencies)
+    }
+
+    pub fn spawn_with_dependencies_with_priority<T>(
+        &self,
+        f: T,
+        dependencies: &TaskDependencies<ClosureTask<O>, ClosureTask<O>, O>,
+        priority: Priority,
+    ) -> Waiting<O>
+    where
+        O: Sync,
+        T: FnOnce(&[Waiting<O>]) -> O + Send + 'static,
+    {
+        self.spawn_task_with_dependencies_with_priority(
+            ClosureTask::with_dependencies(f),
+            dependencies,
+            priority,
+        )
+    }
+}
+
+impl SimboliThread<ClosureTask<AnyOutput>, ClosureTask<AnyOutput>, AnyOutput> {
+    pub fn spawn_typed<T, R>(&self, f: T) -> Waiting<R>
+    where
+        T: FnOnce() -> R + Send + 'static,
+        R: Send + Sync + 'static,
+    {
+        self.spawn_task(ClosureTask::typed(f)).downcast()
+    }
+
+    /// the output of each dependency is read with `Waiting::downcast_ref`
+    pub fn spawn_typed_with_dependencies<T, R>(
+        &self,
+        f: T,
+        dependencies: &TaskDependencies<ClosureTask<AnyOutput>, ClosureTask<AnyOutput>, AnyOutput>,
+    ) -> Waiting<R>
+    where
+        T: FnOnce(&[Waiting<AnyOutput>]) -> R + Send + 'static,
+        R: Send + Sync + 'static,
+    {
+        self.spawn_task_with_dependencies(ClosureTask::typed_with_dependencies(f), dependencies)
+            .downcast()
+    }
+
+    /// `f` runs once every task of `dependencies`, a tuple of `&Waiting<T>`, is done and gets
+    /// their typed outputs. a failed dependency fails the task, same as `spawn_after`
+    pub fn spawn_typed_after<D, T, R>(&self, dependencies: D, f: T) -> Waiting<R>
+    where
+        D: TypedDependencies,
+        T: for<'a> FnOnce(D::Outputs<'a>) -> R + Send + 'static,
+        R: Send + Sync + 'static,
+    {
+        self.list_core
+            .spawn_after(
+                &dependencies.erased(),
+                ClosureTask::typed_with_dependencies(
+                    move |dependencies: &[Waiting<AnyOutput>]| f(D::outputs(dependencies)),
+                ),
+            )
+            .downcast()
+    }
+}
@@ -1,32 +1,55 @@
-use std::sync::Arc;
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    ArrTaskDependenciesWithDependenciesTrait, ListCore, OutputTrait, TaskDependencies, TaskTrait,
-    TaskWithDependenciesTrait, ThreadPoolCore,
-    simboli_thread::list_core::{ArrTaskDependenciesTrait, Waiting},
+    GraphSnapshot, IdleStrategy, ListCore, OutputTrait, PeriodicHandle, PeriodicMode, Priority,
+    SimboliThreadBuilder, TaskDependencies, TaskGraph, TaskGraphError, TaskOnceTrait,
+    TaskWithDependenciesOnceTrait, ThreadPoolCore,
+    simboli_thread::{
+        list_core::{ArrTaskDependenciesTrait, ArrTaskDependenciesWithDependenciesTrait, Waiting},
+        thread_pool_core::PoolConfig,
+    },
 };
 
-pub struct SimboliThread<F, FD, O, const N: usize, const Q: usize>
+pub struct SimboliThread<F, FD, O>
 where
-    F: TaskTrait<O> + 'static + Send,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send + Send,
 {
     // List Core
-    list_core: Arc<ListCore<F, FD, O>>,
+    pub(crate) list_core: Arc<ListCore<F, FD, O>>,
     // thread pool Core
-    thread_pool_core: ThreadPoolCore<F, FD, O, N, Q>,
+    thread_pool_core: ThreadPoolCore<F, FD, O>,
 }
 
-impl<F, FD, O, const N: usize, const Q: usize> SimboliThread<F, FD, O, N, Q>
+impl<F, FD, O> SimboliThread<F, FD, O>
 where
-    F: TaskTrait<O> + 'static + Send,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
-    pub fn init() -> SimboliThread<F, FD, O, N, Q> {
-        let list_core = Arc::new(ListCore::<F, FD, O>::init());
-        let thread_pool_core = ThreadPoolCore::<F, FD, O, N, Q>::init(list_core.clone());
+    /// create thread pool with the default configuration, see `SimboliThreadBuilder::new`
+    pub fn init() -> SimboliThread<F, FD, O> {
+        Self::builder().build()
+    }
+
+    /// create thread pool with custom idle strategy for the workers
+    pub fn init_with_idle_strategy(idle_strategy: IdleStrategy) -> SimboliThread<F, FD, O> {
+        Self::builder().idle_strategy(idle_strategy).build()
+    }
+
+    /// configure the thread pool at runtime
+    pub fn builder() -> SimboliThreadBuilder<F, FD, O> {
+        SimboliThreadBuilder::new()
+    }
+
+    pub(crate) fn init_with_config(config: PoolConfig) -> SimboliThread<F, FD, O> {
+        let list_core = ListCore::<F, FD, O>::init_with_config(&config).into_shared();
+        let thread_pool_core = ThreadPoolCore::<F, FD, O>::init(list_core.clone(), config);
         Self {
             list_core,
             thread_pool_core,
@@ -37,17 +60,73 @@ where
         self.list_core.spawn_task(f)
     }
 
-    // pub fn spawn_task_dependencies_with_dependencies<D, const NF: usize>(
-    //     &self,
-    //     dependencies: D,
-    //     with_dependencies: &TaskDependencies<F, FD, O>,
-    // ) -> TaskDependencies<F, FD, O>
-    // where
-    //     D: ArrTaskDependenciesWithDependenciesTrait<FD, O, NF>,
-    // {
-    //     self.list_core
-    //         .spawn_task_dependencies_with_dependencies(dependencies, &with_dependencies)
-    // }
+    /// poll `future` on the workers, a woken future is pushed to the swap stack again.
+    /// `join` waits for spawned futures to be done
+    pub fn spawn_future<T>(&self, future: T) -> Waiting<O>
+    where
+        T: Future<Output = O> + Send + 'static,
+    {
+        self.list_core.spawn_future(future)
+    }
+
+    /// spawn a task ahead of, or behind, the tasks of lower or higher priority.
+    /// `spawn_task` uses `Priority::Normal`
+    pub fn spawn_task_with_priority(&self, f: F, priority: Priority) -> Waiting<O> {
+        self.list_core.spawn_task_with_priority(f, priority)
+    }
+
+    /// spawn a task once `delay` has passed, the timer thread starts with the first delayed task.
+    /// `join` waits for delayed tasks
+    pub fn spawn_task_after_delay(&self, delay: Duration, f: F) -> Waiting<O> {
+        self.spawn_task_at(Instant::now() + delay, f)
+    }
+
+    /// spawn a task at `deadline`, a deadline in the past spawns it right away
+    pub fn spawn_task_at(&self, deadline: Instant, f: F) -> Waiting<O> {
+        self.list_core.spawn_at(deadline, f)
+    }
+
+    /// run a task created by `task` every `interval` on the workers until the handle is stopped,
+    /// the first run is after one `interval`. panics if `interval` is zero
+    pub fn spawn_task_periodic<T>(
+        &self,
+        interval: Duration,
+        mode: PeriodicMode,
+        task: T,
+    ) -> PeriodicHandle
+    where
+        T: Fn() -> F + Send + Sync + 'static,
+    {
+        self.list_core
+            .spawn_periodic(interval, mode, Arc::new(task))
+    }
+
+    /// dependencies waiting on other dependencies, each task runs after `with_dependencies` are done.
+    /// the result can be the input of the next level
+    pub fn spawn_task_dependencies_with_dependencies<D, const NF: usize>(
+        &self,
+        dependencies: D,
+        with_dependencies: &TaskDependencies<F, FD, O>,
+    ) -> TaskDependencies<F, FD, O>
+    where
+        D: ArrTaskDependenciesWithDependenciesTrait<FD, O, NF>,
+    {
+        self.list_core
+            .spawn_task_dependencies_with_dependencies(dependencies, with_dependencies)
+    }
+
+    /// dependencies waiting on other dependencies, from an iterator
+    pub fn spawn_task_dependencies_with_dependencies_iter<I>(
+        &self,
+        dependencies: I,
+        with_dependencies: &TaskDependencies<F, FD, O>,
+    ) -> TaskDependencies<F, FD, O>
+    where
+        I: IntoIterator<Item = FD>,
+    {
+        self.list_core
+            .spawn_task_dependencies_with_dependencies_iter(dependencies, with_dependencies)
+    }
 
     pub fn spawn_task_dependencies<D, const NF: usize>(
         &self,
@@ -59,6 +138,15 @@ where
         self.list_core.spawn_task_dependencies(dependencies)
     }
 
+    /// dependencies from an iterator, the number of tasks is known at runtime.
+    /// an empty iterator gives dependencies that are already done
+    pub fn spawn_task_dependencies_iter<I>(&self, dependencies: I) -> TaskDependencies<F, FD, O>
+    where
+        I: IntoIterator<Item = F>,
+    {
+        self.list_core.spawn_task_dependencies_iter(dependencies)
+    }
+
     pub fn spawn_task_with_dependencies(
         &self,
         task: FD,
@@ -68,7 +156,66 @@ where
             .spawn_task_with_dependencies(task, dependencies, None)
     }
 
-    /// joining threads in thread pools, does not ensure that all tasks have completed execution before the thread stops
+    /// `spawn_task_with_dependencies` with the priority the task gets once `dependencies` are done
+    pub fn spawn_task_with_dependencies_with_priority(
+        &self,
+        task: FD,
+        dependencies: &TaskDependencies<F, FD, O>,
+        priority: Priority,
+    ) -> Waiting<O> {
+        self.list_core.spawn_task_with_dependencies_with_priority(
+            task,
+            dependencies,
+            None,
+            priority,
+        )
+    }
+
+    /// spawn a task after the tasks of `dependencies` are done, the handles can come from
+    /// unrelated `spawn_task` calls. the task receives their outputs in the same order, `O` is
+    /// `Sync` because the handles can read them at the same time
+    pub fn spawn_after(&self, dependencies: &[&Waiting<O>], task: FD) -> Waiting<O>
+    where
+        O: Sync,
+    {
+        self.list_core.spawn_after(dependencies, task)
+    }
+
+    /// `spawn_after` with the priority the task gets once `dependencies` are done
+    pub fn spawn_after_with_priority(
+        &self,
+        dependencies: &[&Waiting<O>],
+        task: FD,
+        priority: Priority,
+    ) -> Waiting<O>
+    where
+        O: Sync,
+    {
+        self.list_core
+            .spawn_after_with_priority(dependencies, task, priority)
+    }
+
+    /// spawn every task of the graph, the `Waiting` of each task is at the index of its `TaskNode`.
+    /// nothing is spawned if the graph has a cycle
+    pub fn spawn_graph(
+        &self,
+        graph: TaskGraph<F, FD, O>,
+    ) -> Result<Vec<Waiting<O>>, TaskGraphError> {
+        self.list_core.spawn_graph(graph)
+    }
+
+    /// pending tasks with their dependencies, `None` unless `SimboliThreadBuilder::graph_tracing` is on.
+    /// `GraphSnapshot::to_dot` renders it as Graphviz DOT
+    pub fn graph_snapshot(&self) -> Option<GraphSnapshot> {
+        self.list_core.graph_snapshot()
+    }
+
+    /// number of threads in the thread pool
+    pub fn threads(&self) -> usize {
+        self.thread_pool_core.threads
+    }
+
+    // joining threads in thread pools, does not ensure that all tasks have completed execution before the thread stops
     // pub fn join_directly(self) {
     //     self.thread_pool_core.join_directly();
     // }
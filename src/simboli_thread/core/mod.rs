@@ -1,2 +1,18 @@
 mod core;
 pub use core::*;
+
+mod closure;
+pub use closure::*;
+
+mod builder;
+pub use builder::*;
+
+mod parallel;
+pub use parallel::{ParIter, ParMap};
+
+mod scope;
+pub use scope::Scope;
+
+mod worker;
+pub(crate) use worker::enter;
+pub use worker::{ClosureWorker, Worker, current, join};
@@ -0,0 +1,225 @@
+use std::ops::Range;
+
+use crate::{ClosureThread, Waiting};
+
+// tasks for each thread when the task size is not set, idle workers steal the rest
+const TASKS_PER_THREAD: usize = 4;
+
+/// parallel iterator over indexed items, from `par_iter` or `par_chunks`.
+/// the items are split into scoped tasks of `task_size` items, outputs keep the input order
+pub struct ParIter<'pool, I, G, O>
+where
+    G: Fn(usize) -> I + Sync,
+    O: 'static + Send,
+{
+    thread_pool: &'pool ClosureThread<O>,
+    len: usize,
+    // item at index
+    get: G,
+    // 0 for a few tasks for each thread
+    task_size: usize,
+}
+
+impl<'pool, I, G, O> ParIter<'pool, I, G, O>
+where
+    G: Fn(usize) -> I + Sync,
+    O: 'static + Send,
+{
+    /// number of items
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// number of items in each task
+    pub fn task_size(mut self, task_size: usize) -> ParIter<'pool, I, G, O> {
+        assert!(task_size > 0, "task size must be greater than zero");
+        self.task_size = task_size;
+        self
+    }
+
+    pub fn map<U, M>(self, map: M) -> ParMap<'pool, I, G, M, O>
+    where
+        M: Fn(I) -> U + Sync,
+    {
+        ParMap { iter: self, map }
+    }
+
+    pub fn for_each<E>(self, f: E)
+    where
+        E: Fn(I) + Sync,
+    {
+        let get = &self.get;
+        self.run(|range| range.for_each(|index| f(get(index))));
+    }
+
+    // run `task` for the item range of each task, the outputs are in the order of the ranges.
+    // a panic of a task is raised again here once every task is done
+    fn run<R, T>(&self, task: T) -> Vec<R>
+    where
+        R: Send,
+        T: Fn(Range<usize>) -> R + Sync,
+    {
+        if self.len == 0 {
+            return Vec::new();
+        }
+
+        let task_size = match self.task_size {
+            0 => self
+                .len
+                .div_ceil(self.thread_pool.threads() * TASKS_PER_THREAD),
+            task_size => task_size,
+        };
+        let ranges: Vec<Range<usize>> = (0..self.len)
+            .step_by(task_size)
+            .map(|start| start..(start + task_size).min(self.len))
+            .collect();
+        let mut outputs: Vec<Option<R>> = ranges.iter().map(|_| None).collect();
+
+        let task = &task;
+        // unit tasks, the output type of the thread pool is not needed
+        let waitings: Vec<Waiting<()>> = self.thread_pool.scope(|s| {
+            ranges
+                .into_iter()
+                .zip(outputs.iter_mut())
+                .map(|(range, output)| s.spawn_unit(move || *output = Some(task(range))))
+                .collect()
+        });
+
+        for waiting in waitings {
+            if let Err(err) = waiting.collect() {
+                panic!("parallel task failed, {}", err);
+            }
+        }
+
+        outputs
+            .into_iter()
+            .map(|output| output.expect("parallel task has no output"))
+            .collect()
+    }
+}
+
+/// `ParIter` with a function applied to each item
+pub struct ParMap<'pool, I, G, M, O>
+where
+    G: Fn(usize) -> I + Sync,
+    O: 'static + Send,
+{
+    iter: ParIter<'pool, I, G, O>,
+    map: M,
+}
+
+impl<I, G, M, U, O> ParMap<'_, I, G, M, O>
+where
+    G: Fn(usize) -> I + Sync,
+    M: Fn(I) -> U + Sync,
+    U: Send,
+    O: 'static + Send,
+{
+    /// outputs in the order of the items
+    pub fn collect(self) -> Vec<U> {
+        let (get, map) = (&self.iter.get, &self.map);
+        self.iter
+            .run(|range| range.map(|index| map(get(index))).collect::<Vec<U>>())
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    pub fn for_each<E>(self, f: E)
+    where
+        E: Fn(U) + Sync,
+    {
+        let map = &self.map;
+        self.iter.for_each(|item| f(map(item)));
+    }
+
+    /// combine the outputs with `op` in the order of the items, each task starts from `identity`.
+    /// `op` only has to be associative
+    pub fn reduce<ID, OP>(self, identity: ID, op: OP) -> U
+    where
+        ID: Fn() -> U + Sync,
+        OP: Fn(U, U) -> U + Sync,
+    {
+        let (get, map) = (&self.iter.get, &self.map);
+        self.iter
+            .run(|range| range.fold(identity(), |output, index| op(output, map(get(index)))))
+            .into_iter()
+            .reduce(&op)
+            .unwrap_or_else(identity)
+    }
+}
+
+impl<O> ClosureThread<O>
+where
+    O: 'static + Send,
+{
+    /// parallel iterator over the items of a slice
+    pub fn par_iter<'data, T>(
+        &self,
+        items: &'data [T],
+    ) -> ParIter<'_, &'data T, impl Fn(usize) -> &'data T + Sync, O>
+    where
+        T: Sync,
+    {
+        ParIter {
+            thread_pool: self,
+            len: items.len(),
+            get: move |index| &items[index],
+            task_size: 0,
+        }
+    }
+
+    /// parallel iterator over chunks of `chunk_size` items, the last chunk holds the
+    /// remaining items and can be smaller
+    pub fn par_chunks<'data, T>(
+        &self,
+        items: &'data [T],
+        chunk_size: usize,
+    ) -> ParIter<'_, &'data [T], impl Fn(usize) -> &'data [T] + Sync, O>
+    where
+        T: Sync,
+    {
+        assert!(chunk_size > 0, "chunk size must be greater than zero");
+        ParIter {
+            thread_pool: self,
+            len: items.len().div_ceil(chunk_size),
+            get: move |index| {
+                let start = index * chunk_size;
+                &items[start..(start + chunk_size).min(items.len())]
+            },
+            task_size: 0,
+        }
+    }
+
+    /// `map` applied to every item in parallel, outputs in the order of the items
+    pub fn par_map<T, U, M>(&self, items: &[T], map: M) -> Vec<U>
+    where
+        T: Sync,
+        U: Send,
+        M: Fn(&T) -> U + Sync,
+    {
+        self.par_iter(items).map(map).collect()
+    }
+
+    pub fn par_for_each<T, E>(&self, items: &[T], f: E)
+    where
+        T: Sync,
+        E: Fn(&T) + Sync,
+    {
+        self.par_iter(items).for_each(f);
+    }
+
+    /// items combined with `op` in their order, see `ParMap::reduce`
+    pub fn par_reduce<T, ID, OP>(&self, items: &[T], identity: ID, op: OP) -> T
+    where
+        T: Clone + Send + Sync,
+        ID: Fn() -> T + Sync,
+        OP: Fn(T, T) -> T + Sync,
+    {
+        self.par_iter(items).map(T::clone).reduce(identity, op)
+    }
+}
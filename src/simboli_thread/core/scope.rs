@@ -0,0 +1,167 @@
+use std::{
+    marker::PhantomData,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Condvar, Mutex},
+    time::Instant,
+};
+
+use crate::{
+    ClosureTask, SimboliThread, Waiting, WaitingError, current,
+    simboli_thread::list_core::ReturnCell,
+};
+
+type ScopedClosure<'scope, O> = Box<dyn FnOnce() -> O + Send + 'scope>;
+
+// number of scoped closures not dropped yet
+struct ScopeCore {
+    pending: Mutex<usize>,
+    condvar: Condvar,
+}
+
+impl ScopeCore {
+    fn is_done(&self) -> bool {
+        *self.pending.lock().unwrap() == 0
+    }
+
+    fn wait_deadline(&self, deadline: Instant) {
+        let pending = self.pending.lock().unwrap();
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        drop(
+            self.condvar
+                .wait_timeout_while(pending, timeout, |pending| *pending > 0)
+                .unwrap(),
+        );
+    }
+
+    fn wait(&self) {
+        let pending = self.pending.lock().unwrap();
+        drop(
+            self.condvar
+                .wait_while(pending, |pending| *pending > 0)
+                .unwrap(),
+        );
+    }
+}
+
+// the closure and its borrows are dropped before the scope is told, run or not
+struct ScopedTask<'scope, O> {
+    f: Option<ScopedClosure<'scope, O>>,
+    scope_core: Arc<ScopeCore>,
+}
+
+impl<O> Drop for ScopedTask<'_, O> {
+    fn drop(&mut self) {
+        drop(self.f.take());
+
+        let mut pending = self.scope_core.pending.lock().unwrap();
+        *pending -= 1;
+        if *pending == 0 {
+            self.scope_core.condvar.notify_all();
+        }
+    }
+}
+
+/// tasks borrowing from the stack of `SimboliThread::scope`
+pub struct Scope<'scope, 'env: 'scope, O>
+where
+    O: 'static + Send,
+{
+    thread_pool: &'scope SimboliThread<ClosureTask<O>, ClosureTask<O>, O>,
+    scope_core: Arc<ScopeCore>,
+    // invariant lifetimes, same as std::thread::Scope
+    _scope: PhantomData<&'scope mut &'scope ()>,
+    _env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'scope, O> Scope<'scope, '_, O>
+where
+    O: 'static + Send,
+{
+    /// spawn a closure that can borrow anything outliving the scope, it is done or dropped
+    /// before `SimboliThread::scope` returns
+    pub fn spawn<T>(&'scope self, f: T) -> Waiting<O>
+    where
+        T: FnOnce() -> O + Send + 'scope,
+    {
+        *self.scope_core.pending.lock().unwrap() += 1;
+        let mut scoped_task = ScopedTask {
+            f: Some(Box::new(f)),
+            scope_core: self.scope_core.clone(),
+        };
+
+        let closure: ScopedClosure<'scope, O> = Box::new(move || (scoped_task.f.take().unwrap())());
+        // the scope waits until every scoped task is dropped, the borrows outlive the closure
+        let closure: ScopedClosure<'static, O> = unsafe {
+            std::mem::transmute::<ScopedClosure<'scope, O>, ScopedClosure<'static, O>>(closure)
+        };
+
+        self.thread_pool.spawn(closure)
+    }
+
+    // `spawn` for a closure without an output of type O, it runs as a type erased task
+    pub(crate) fn spawn_unit<T>(&'scope self, f: T) -> Waiting<()>
+    where
+        T: FnOnce() + Send + 'scope,
+    {
+        *self.scope_core.pending.lock().unwrap() += 1;
+        let mut scoped_task = ScopedTask {
+            f: Some(Box::new(f)),
+            scope_core: self.scope_core.clone(),
+        };
+
+        let closure: ScopedClosure<'scope, ()> =
+            Box::new(move || (scoped_task.f.take().unwrap())());
+        // the scope waits until every scoped task is dropped, the borrows outlive the closure
+        let closure: ScopedClosure<'static, ()> = unsafe {
+            std::mem::transmute::<ScopedClosure<'scope, ()>, ScopedClosure<'static, ()>>(closure)
+        };
+
+        let return_ptr =
+            self.thread_pool
+                .list_core
+                .spawn_erased(Box::new(move |return_ptr: &ReturnCell| {
+                    closure();
+                    return_ptr.store(Ok::<(), WaitingError>(()))
+                }));
+        Waiting::init(return_ptr)
+    }
+}
+
+impl<O> SimboliThread<ClosureTask<O>, ClosureTask<O>, O>
+where
+    O: 'static + Send,
+{
+    /// spawn tasks borrowing local data, like `std::thread::scope`. returns once every task
+    /// spawned in the scope is done, a panicking task is returned by its `Waiting`.
+    /// on a worker of a thread pool, the worker runs other tasks while waiting
+    pub fn scope<'env, T, R>(&self, f: T) -> R
+    where
+        T: for<'scope> FnOnce(&'scope Scope<'scope, 'env, O>) -> R,
+    {
+        let scope = Scope {
+            thread_pool: self,
+            scope_core: Arc::new(ScopeCore {
+                pending: Mutex::new(0),
+                condvar: Condvar::new(),
+            }),
+            _scope: PhantomData,
+            _env: PhantomData,
+        };
+
+        // wait even if f panics, the tasks still borrow its data
+        let output = panic::catch_unwind(AssertUnwindSafe(|| f(&scope)));
+
+        match current::<ClosureTask<O>, ClosureTask<O>, O>() {
+            Some(worker) => worker.help_until(
+                || scope.scope_core.is_done(),
+                |deadline| scope.scope_core.wait_deadline(deadline),
+            ),
+            None => scope.scope_core.wait(),
+        }
+
+        match output {
+            Ok(output) => output,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, atomic::Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    ClosureTask, ExecTask, OutputTrait, Priority, TaskOnceTrait, TaskPanic,
+    TaskWithDependenciesOnceTrait, Waiting, WaitingError,
+    simboli_thread::thread_pool_core::ThreadUnit,
+};
+
+type JoinClosure<'a, O> = Box<dyn FnOnce() -> O + Send + 'a>;
+
+// task id of a panic caught by `join` outside a worker, it is not a task of any thread pool
+const NO_TASK_ID: u64 = u64::MAX;
+
+// sleep of a waiting worker with nothing to run, woken up earlier when the output is stored
+const HELP_INTERVAL: Duration = Duration::from_micros(100);
+
+// nested `Worker::wait` taking new tasks from the primary list or other workers, deeper waits only
+// run the local queue so the stack of the worker stays bounded
+const MAX_HELP_DEPTH: usize = 8;
+
+thread_local! {
+    // thread unit of this thread, while it is running as worker of a thread pool
+    static CURRENT_WORKER: RefCell<Option<Box<dyn Any>>> = const { RefCell::new(None) };
+    // number of nested `Worker::wait` on this thread
+    static HELP_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+// clear the worker of this thread on drop
+pub(crate) struct WorkerGuard;
+
+impl Drop for WorkerGuard {
+    fn drop(&mut self) {
+        CURRENT_WORKER.with_borrow_mut(|current_worker| *current_worker = None);
+    }
+}
+
+pub(crate) fn enter<F, FD, O>(thread_unit: Arc<ThreadUnit<F, FD, O>>) -> WorkerGuard
+where
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    CURRENT_WORKER.with_borrow_mut(|current_worker| *current_worker = Some(Box::new(thread_unit)));
+    WorkerGuard
+}
+
+/// worker running the current task, `None` outside of a thread pool or when the thread pool has
+/// other task types
+pub fn current<F, FD, O>() -> Option<Worker<F, FD, O>>
+where
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    CURRENT_WORKER.with_borrow(|current_worker| {
+        current_worker
+            .as_ref()?
+            .downcast_ref::<Arc<ThreadUnit<F, FD, O>>>()
+            .map(|thread_unit| Worker {
+                thread_unit: thread_unit.clone(),
+                _marker: PhantomData,
+            })
+    })
+}
+
+/// run `a` and `b` in parallel and return both outputs, a panic is returned as
+/// `WaitingError::Panicked`. on a worker of a `ClosureThread<O>`, see `Worker::join`.
+/// outside a worker both run on the current thread, the panic has `u64::MAX` as task id
+pub fn join<A, B, O>(a: A, b: B) -> (Result<O, WaitingError>, Result<O, WaitingError>)
+where
+    A: FnOnce() -> O + Send,
+    B: FnOnce() -> O + Send,
+    O: 'static + Send,
+{
+    match current::<ClosureTask<O>, ClosureTask<O>, O>() {
+        Some(worker) => worker.join(a, b),
+        None => (catch_panic(a), catch_panic(b)),
+    }
+}
+
+fn catch_panic<T, O>(f: T) -> Result<O, WaitingError>
+where
+    T: FnOnce() -> O,
+{
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|payload| WaitingError::Panicked(TaskPanic::from_payload(NO_TASK_ID, payload)))
+}
+
+/// worker of a closure thread pool
+pub type ClosureWorker<O> = Worker<ClosureTask<O>, ClosureTask<O>, O>;
+
+/// handle to the worker running the current task, from `current`. it stays on the worker thread
+pub struct Worker<F, FD, O>
+where
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    thread_unit: Arc<ThreadUnit<F, FD, O>>,
+    // only the worker thread pushes to its local queue
+    _marker: PhantomData<*const ()>,
+}
+
+impl<F, FD, O> Worker<F, FD, O>
+where
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    /// id of the thread in the thread pool
+    pub fn id(&self) -> usize {
+        self.thread_unit.id
+    }
+
+    /// spawn into the local queue of this worker, other workers can steal it.
+    /// the task runs right away on this worker when the local queue is full
+    pub fn spawn_task(&self, f: F) -> Waiting<O> {
+        let (waiting_task_ptr, return_ptr) = self.thread_unit.list_core.create_waiting_task(
+            ExecTask::Task(f),
+            None,
+            None,
+            self.priority(),
+            true,
+        );
+        if !self.thread_unit.push_local(waiting_task_ptr) {
+            self.thread_unit.run_waiting_task(waiting_task_ptr);
+        }
+
+        Waiting::init(return_ptr)
+    }
+
+    /// run other tasks until the task of `waiting` is done
+    pub fn wait(&self, waiting: &Waiting<O>) {
+        self.help_until(
+            || waiting.is_ready(),
+            |deadline| {
+                waiting.data_ptr.wait_deadline(deadline);
+            },
+        );
+    }
+
+    // run other tasks until `done`, `sleep` until the deadline when there is nothing to run
+    pub(crate) fn help_until(&self, done: impl Fn() -> bool, sleep: impl Fn(Instant)) {
+        let depth = HELP_DEPTH.get();
+        HELP_DEPTH.set(depth + 1);
+
+        while !done() {
+            if let Some(waiting_task_ptr) = self.thread_unit.pop_local() {
+                self.thread_unit.run_waiting_task(waiting_task_ptr);
+                continue;
+            }
+
+            if depth >= MAX_HELP_DEPTH || !self.thread_unit.fill_local_queue() {
+                // nothing to run, the task is running on another worker
+                sleep(Instant::now() + HELP_INTERVAL);
+            }
+        }
+
+        HELP_DEPTH.set(depth);
+    }
+
+    // tasks spawned by the running task keep the priority of the local queue
+    fn priority(&self) -> Priority {
+        Priority::from_index(self.thread_unit.priority.load(Ordering::Acquire) as usize)
+    }
+
+    /// `b` goes to the local queue, `a` runs on this worker, then this worker runs other tasks
+    /// until `b` is done. `b` runs here too if no other worker has stolen it
+    pub fn join_tasks(&self, a: F, b: F) -> (Waiting<O>, Waiting<O>) {
+        let b = self.spawn_task(b);
+
+        let (waiting_task_ptr, return_ptr) = self.thread_unit.list_core.create_waiting_task(
+            ExecTask::Task(a),
+            None,
+            None,
+            self.priority(),
+            true,
+        );
+        self.thread_unit.run_waiting_task(waiting_task_ptr);
+        let a = Waiting::init(return_ptr);
+
+        self.wait(&b);
+        (a, b)
+    }
+}
+
+impl<O> Worker<ClosureTask<O>, ClosureTask<O>, O>
+where
+    O: 'static + Send,
+{
+    pub fn spawn<T>(&self, f: T) -> Waiting<O>
+    where
+        T: FnOnce() -> O + Send + 'static,
+    {
+        self.spawn_task(ClosureTask::new(f))
+    }
+
+    /// `join_tasks` for closures, a panic is returned as `WaitingError::Panicked`.
+    /// the closures can borrow local data, both are done when `join` returns
+    pub fn join<A, B>(&self, a: A, b: B) -> (Result<O, WaitingError>, Result<O, WaitingError>)
+    where
+        A: FnOnce() -> O + Send,
+        B: FnOnce() -> O + Send,
+    {
+        let a: JoinClosure<'_, O> = Box::new(a);
+        let b: JoinClosure<'_, O> = Box::new(b);
+        // join_tasks runs `a` here and waits until `b` is done, a closure is consumed before its
+        // output is stored so the borrows outlive both closures
+        let (a, b) = unsafe {
+            (
+                std::mem::transmute::<JoinClosure<'_, O>, JoinClosure<'static, O>>(a),
+                std::mem::transmute::<JoinClosure<'_, O>, JoinClosure<'static, O>>(b),
+            )
+        };
+
+        let (a, b) = self.join_tasks(ClosureTask::new(a), ClosureTask::new(b));
+        (a.collect(), b.collect())
+    }
+}
@@ -1,131 +1,233 @@
 use std::{
+    collections::HashSet,
+    future::Future,
     hint::spin_loop,
-    ptr::{self, null_mut},
+    ptr::null_mut,
     sync::{
-        Arc,
+        Arc, Mutex, Weak,
         atomic::{AtomicPtr, AtomicU64, Ordering},
     },
+    time::{Duration, Instant},
 };
 
 use crate::{
-    ArrTaskDependenciesWithDependenciesTrait, ExecTask, TaskDependencies,
-    TaskWithDependenciesTrait,
+    ArrTaskDependenciesWithDependenciesTrait, ContinuationTask, ExecTask, FutureTask,
+    GraphSnapshot, PeriodicHandle, PeriodicMode, SnapshotTask, TaskDependencies, TaskGraph,
+    TaskGraphError, TaskState, TaskWithDependenciesOnceTrait,
     simboli_thread::list_core::{
-        ArrTaskDependenciesTrait, OutputTrait, TaskDependenciesCore, TaskTrait, Waiting,
-        WaitingTask, task_list::TaskList,
+        ArrTaskDependenciesTrait, OutputTrait, ReturnCell, TaskDependenciesCore, TaskOnceTrait,
+        Waiting, WaitingTask,
+        priority::{Priority, PriorityList},
+        task_list::TaskList,
+        timer::Timer,
+        wait::{CellState, Continuation, FutureCore, GraphParent, PeriodicCore, Spawner},
     },
+    simboli_thread::thread_pool_core::{IdleHandler, PoolConfig},
 };
 
 pub struct ListCore<F, FD, O>
 where
-    F: TaskTrait<O> + Send + 'static,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
-    // primary Stack
     id_counter: AtomicU64,
-    start: AtomicPtr<WaitingTask<F, FD, O>>,
-    end: AtomicPtr<WaitingTask<F, FD, O>>,
+    // primary and swap stack of each priority, highest first
+    lists: [PriorityList<F, FD, O>; 3],
 
     // handler
     pub(crate) in_task: Arc<AtomicU64>,
+    pub(crate) idle_handler: IdleHandler,
 
-    // Swap Stack
-    swap_start: AtomicPtr<WaitingTask<F, FD, O>>,
-    swap_end: AtomicPtr<WaitingTask<F, FD, O>>,
+    // delayed tasks, pushed to the swap stack at their deadline
+    timer: Timer<F, FD, O>,
+    // periodic tasks, stopped by join
+    periodic_tasks: Mutex<Vec<Weak<PeriodicCore>>>,
+
+    // pending tasks for graph_snapshot, None when graph tracing is off
+    tracked_tasks: Option<Mutex<Vec<TrackedTask>>>,
+
+    // the list core itself once shared, continuations of its tasks are spawned here
+    this: Weak<ListCore<F, FD, O>>,
+}
+
+struct TrackedTask {
+    return_ptr: Weak<ReturnCell>,
+    dependencies: Vec<Weak<ReturnCell>>,
+}
+
+impl TrackedTask {
+    fn is_pending(&self) -> bool {
+        self.return_ptr.upgrade().is_some_and(|return_ptr| {
+            matches!(return_ptr.state(), CellState::Pending | CellState::Running)
+        })
+    }
 }
 
 impl<F, FD, O> ListCore<F, FD, O>
 where
-    F: TaskTrait<O> + Send + 'static,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
-    pub fn init() -> ListCore<F, FD, O> {
+    pub fn init(graph_tracing: bool) -> ListCore<F, FD, O> {
+        Self::init_with_config(&PoolConfig {
+            graph_tracing,
+            ..PoolConfig::default()
+        })
+    }
+
+    pub(crate) fn init_with_config(config: &PoolConfig) -> ListCore<F, FD, O> {
         Self {
-            // primary Stack
             id_counter: AtomicU64::new(0),
-            start: AtomicPtr::new(ptr::null_mut()),
-            end: AtomicPtr::new(ptr::null_mut()),
+            lists: [
+                PriorityList::init(),
+                PriorityList::init(),
+                PriorityList::init(),
+            ],
 
             // handler
             in_task: Arc::new(AtomicU64::new(0)),
+            idle_handler: IdleHandler::init(),
+
+            timer: Timer::init(config.thread_name.clone()),
+            periodic_tasks: Mutex::new(Vec::new()),
+
+            tracked_tasks: config.graph_tracing.then(|| Mutex::new(Vec::new())),
 
-            // Swap Stack
-            swap_start: AtomicPtr::new(ptr::null_mut()),
-            swap_end: AtomicPtr::new(ptr::null_mut()),
+            this: Weak::new(),
         }
     }
 
-    pub fn is_primary_list_empty(&self) -> bool {
-        self.end.load(Ordering::Acquire).is_null()
+    // shared by the thread pool, tasks can spawn continuations from now on
+    pub(crate) fn into_shared(mut self) -> Arc<ListCore<F, FD, O>> {
+        Arc::new_cyclic(|this| {
+            self.this = this.clone();
+            self
+        })
     }
 
-    pub fn get_waiting_task_from_primary_stack<const N: usize>(
-        &self,
-        len: u32,
-    ) -> Result<TaskList<F, FD, O, N>, &str> {
-        let start_waiting_task = self.start.load(Ordering::Acquire);
-
-        // scanning start from "end"
-        let mut list_task = Vec::new();
-        for _ in 0..N {
-            list_task.push(AtomicPtr::new(null_mut()));
+    fn return_cell(&self, id: u64) -> Arc<ReturnCell> {
+        Arc::new(ReturnCell::init(id, self.this.clone()))
+    }
+
+    fn track(&self, waiting_task: &WaitingTask<F, FD, O>) {
+        if let Some(tracked_tasks) = &self.tracked_tasks {
+            let dependencies = waiting_task
+                .task_dependencies_ptr
+                .as_ref()
+                .map(|dependencies| {
+                    dependencies
+                        .iter()
+                        .map(|waiting| Arc::downgrade(&waiting.data_ptr))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let mut tracked_tasks = tracked_tasks.lock().unwrap();
+            // forget finished tasks before growing
+            if tracked_tasks.len() == tracked_tasks.capacity() {
+                tracked_tasks.retain(TrackedTask::is_pending);
+            }
+            tracked_tasks.push(TrackedTask {
+                return_ptr: Arc::downgrade(&waiting_task.waiting_return_ptr),
+                dependencies,
+            });
         }
-        let mut count: u64 = 0;
-        unsafe {
-            loop {
-                let waiting_task = self.end.load(Ordering::Acquire);
-                if waiting_task.is_null() {
-                    return Err("Primary list empty");
-                }
+    }
 
-                let next_waiting_task = (*waiting_task).next.load(Ordering::Acquire);
-                if next_waiting_task.is_null() {
-                    // check, is this last task?
-                    if start_waiting_task == waiting_task {
-                        // this last task
-                        // store the task
-                        // // start from bottom
-                        list_task[(N - 1) - count as usize] = AtomicPtr::new(waiting_task);
-                        // update start
-                        self.start.store(null_mut(), Ordering::Release);
-                        // update end
-                        self.end.store(null_mut(), Ordering::Release);
-                        // update counter
-                        count += 1;
-                        break;
-                    } else {
-                        // waiting
-                        spin_loop();
-                        continue;
-                    };
+    pub fn graph_snapshot(&self) -> Option<GraphSnapshot> {
+        let tracked_tasks = self.tracked_tasks.as_ref()?;
+        let mut tracked_tasks = tracked_tasks.lock().unwrap();
+        tracked_tasks.retain(TrackedTask::is_pending);
+
+        let mut snapshot = GraphSnapshot::default();
+        let mut seen = HashSet::new();
+        let mut upstream = Vec::new();
+        for tracked_task in tracked_tasks.iter() {
+            let Some(return_ptr) = tracked_task.return_ptr.upgrade() else {
+                continue;
+            };
+            let dependencies: Vec<Arc<ReturnCell>> = tracked_task
+                .dependencies
+                .iter()
+                .filter_map(Weak::upgrade)
+                .collect();
+
+            let state = match return_ptr.state() {
+                CellState::Running => TaskState::Running,
+                CellState::Done => TaskState::Done,
+                CellState::Cancelled => TaskState::Cancelled,
+                CellState::Pending
+                    if dependencies.iter().any(|dependency| {
+                        matches!(dependency.state(), CellState::Pending | CellState::Running)
+                    }) =>
+                {
+                    TaskState::Waiting
                 }
+                CellState::Pending => TaskState::Queued,
+            };
 
-                // store the task
-                // // start from bottom
-
-                list_task[(N - 1) - count as usize] = AtomicPtr::new(waiting_task);
-                // update end
-                self.end.store(next_waiting_task, Ordering::Release);
-                count += 1;
+            seen.insert(return_ptr.id());
+            snapshot.tasks.push(SnapshotTask {
+                id: return_ptr.id(),
+                label: return_ptr.label().map(str::to_string),
+                state,
+                dependencies: dependencies
+                    .iter()
+                    .map(|dependency| dependency.id())
+                    .collect(),
+            });
+            upstream.extend(dependencies);
+        }
 
-                if count >= len as u64 {
-                    break;
-                }
+        // dependencies that are not pending anymore, without their own dependencies
+        for return_ptr in upstream {
+            if seen.insert(return_ptr.id()) {
+                snapshot.tasks.push(SnapshotTask {
+                    id: return_ptr.id(),
+                    label: return_ptr.label().map(str::to_string),
+                    state: match return_ptr.state() {
+                        CellState::Done => TaskState::Done,
+                        CellState::Cancelled => TaskState::Cancelled,
+                        CellState::Running => TaskState::Running,
+                        CellState::Pending => TaskState::Queued,
+                    },
+                    dependencies: Vec::new(),
+                });
             }
         }
 
-        let list_task = TaskList {
-            list: list_task.try_into().unwrap(),
-            count,
-            top: (N as u64) - count,
-            bottom: N as u64,
-        };
+        Some(snapshot)
+    }
+
+    pub fn is_primary_list_empty(&self) -> bool {
+        self.lists.iter().all(PriorityList::is_primary_list_empty)
+    }
+
+    pub fn is_swap_list_empty(&self) -> bool {
+        self.lists.iter().all(PriorityList::is_swap_list_empty)
+    }
 
-        Ok(list_task)
+    // batch from the highest priority with tasks in its primary list
+    pub fn get_waiting_task_from_primary_stack(
+        &self,
+        len: usize,
+    ) -> Result<TaskList<F, FD, O>, &str> {
+        for (index, list) in self.lists.iter().enumerate() {
+            if list.is_primary_list_empty() {
+                continue;
+            }
+            if let Ok(mut task_list) = list.get_waiting_task_from_primary_stack(len) {
+                task_list.priority = Priority::from_index(index);
+                return Ok(task_list);
+            }
+        }
+
+        Err("Primary list empty")
     }
 
+    // tasks released by a dependency group, each goes to the list of its own priority
     pub fn insert_list_from_harvesting(
         &self,
         harvesting_start: AtomicPtr<WaitingTask<F, FD, O>>,
@@ -135,26 +237,66 @@ where
         let harvesting_end = harvesting_end.swap(null_mut(), Ordering::AcqRel);
         if !harvesting_end.is_null() {
             let harvesting_start = harvesting_start.swap(null_mut(), Ordering::AcqRel);
-            let prev_start = self.start.swap(harvesting_start, Ordering::AcqRel);
-            if !prev_start.is_null() {
-                unsafe {
-                    (*prev_start).next.store(harvesting_end, Ordering::Release);
+
+            // split end -> .. -> start into one list for each priority, keeping the order
+            let mut starts: [*mut WaitingTask<F, FD, O>; Priority::LEVELS.len()] =
+                [null_mut(); Priority::LEVELS.len()];
+            let mut ends = starts;
+            let mut waiting_task = harvesting_end;
+            unsafe {
+                loop {
+                    let next_waiting_task = if waiting_task == harvesting_start {
+                        null_mut()
+                    } else {
+                        // the next task may not be linked yet
+                        loop {
+                            let next_waiting_task = (*waiting_task).next.load(Ordering::Acquire);
+                            if !next_waiting_task.is_null() {
+                                break next_waiting_task;
+                            }
+                            spin_loop();
+                        }
+                    };
+                    (*waiting_task).next.store(null_mut(), Ordering::Release);
+
+                    let index = (*waiting_task).priority.index();
+                    if ends[index].is_null() {
+                        ends[index] = waiting_task;
+                    } else {
+                        (*starts[index]).next.store(waiting_task, Ordering::Release);
+                    }
+                    starts[index] = waiting_task;
+
+                    if next_waiting_task.is_null() {
+                        break;
+                    }
+                    waiting_task = next_waiting_task;
+                }
+            }
+
+            for (index, list) in self.lists.iter().enumerate() {
+                if !ends[index].is_null() {
+                    list.insert_list_to_primary(starts[index], ends[index]);
                 }
-            } else {
-                self.end.store(harvesting_end, Ordering::Release);
             }
+
+            // released task landed, wake a parked worker
+            self.idle_handler.wake_one();
         }
     }
 
+    // swap every empty primary list with its swap stack, error when all primary lists stay empty
     pub fn swap_to_primary(&self) -> Result<(), &str> {
-        let end = self.swap_end.swap(null_mut(), Ordering::AcqRel);
-        if !end.is_null() {
-            let start = self.swap_start.swap(null_mut(), Ordering::AcqRel);
-            self.start.store(start, Ordering::Release);
-            self.end.store(end, Ordering::Release);
-            Ok(())
-        } else {
+        for list in self.lists.iter() {
+            if list.is_primary_list_empty() {
+                let _ = list.swap_to_primary();
+            }
+        }
+
+        if self.is_primary_list_empty() {
             Err("SWAP STACK EMPTY")
+        } else {
+            Ok(())
         }
     }
 
@@ -166,32 +308,52 @@ where
     where
         D: ArrTaskDependenciesWithDependenciesTrait<FD, O, NF>,
     {
-        // create dependencies
-        let task_dependencies_core_ptr: &'static TaskDependenciesCore<F, FD, O> =
-            Box::leak(Box::new(TaskDependenciesCore::init(NF)));
+        self.spawn_task_dependencies_with_dependencies_iter(
+            dependencies.task_list(),
+            with_dependencies,
+        )
+    }
+
+    pub fn spawn_task_dependencies_with_dependencies_iter<I>(
+        &self,
+        dependencies: I,
+        with_dependencies: &TaskDependencies<F, FD, O>,
+    ) -> TaskDependencies<F, FD, O>
+    where
+        I: IntoIterator<Item = FD>,
+    {
+        // the counter must be known before the first task is running
+        let task_list: Vec<FD> = dependencies.into_iter().collect();
+
+        // create dependencies, nothing to wait for when empty
+        let task_dependencies_core_ptr = if task_list.is_empty() {
+            Arc::new(TaskDependenciesCore::blank())
+        } else {
+            Arc::new(TaskDependenciesCore::init(task_list.len()))
+        };
 
         // output
-        let mut waiting_output = Vec::with_capacity(NF);
+        let mut waiting_output = Vec::with_capacity(task_list.len());
 
         // task_dependencies
-        for task in dependencies.task_list() {
+        for task in task_list {
             // create waiting task
             if task.is_with_dependencies() {
                 let waiting_task = self.spawn_task_with_dependencies(
                     task,
                     with_dependencies,
-                    Some(task_dependencies_core_ptr),
+                    Some(task_dependencies_core_ptr.clone()),
                 );
                 waiting_output.push(waiting_task);
             } else {
-                let waiting_task = self.spawn_task_fd(task, Some(task_dependencies_core_ptr));
+                let waiting_task =
+                    self.spawn_task_fd(task, Some(task_dependencies_core_ptr.clone()));
                 waiting_output.push(waiting_task);
             }
         }
 
-        let waiting_output_leak: &'static mut Vec<Waiting<O>> = Box::leak(Box::new(waiting_output));
         TaskDependencies {
-            waiting_list: waiting_output_leak,
+            waiting_list: Arc::new(waiting_output),
             task_dependencies_ptr: task_dependencies_core_ptr,
         }
     }
@@ -200,31 +362,31 @@ where
         &self,
         task: FD,
         dependencies: &TaskDependencies<F, FD, O>,
-        task_dependencies_core_ptr: Option<&'static TaskDependenciesCore<F, FD, O>>,
+        task_dependencies_core_ptr: Option<Arc<TaskDependenciesCore<F, FD, O>>>,
     ) -> Waiting<O> {
-        // main thread only focus in swap queue, base on swap start
-        // dependecies leak
-        // let dependencies: &'static TaskDependencies<F, O> = Box::leak(Box::new(dependencies));
-        // update in_task handler
-        self.in_task.fetch_add(1, Ordering::SeqCst);
-        // create return_ptr
-        let return_ptr: &'static AtomicPtr<O> = Box::leak(Box::new(AtomicPtr::new(null_mut())));
-        // create waiting task
-
-        let waiting_task = WaitingTask {
-            id: self.id_counter.fetch_add(1, Ordering::Release),
-            task: ExecTask::TaskWithDependencies(task),
-            next: AtomicPtr::new(ptr::null_mut()),
-            waiting_return_ptr: return_ptr,
-            task_dependencies_core_ptr: if let Some(ptr) = task_dependencies_core_ptr {
-                ptr
-            } else {
-                Box::leak(Box::new(TaskDependenciesCore::blank()))
-            },
-            task_dependencies_ptr: dependencies.waiting_list,
-        };
+        self.spawn_task_with_dependencies_with_priority(
+            task,
+            dependencies,
+            task_dependencies_core_ptr,
+            Priority::Normal,
+        )
+    }
 
-        let waiting_task_ptr = Box::into_raw(Box::new(waiting_task));
+    pub fn spawn_task_with_dependencies_with_priority(
+        &self,
+        task: FD,
+        dependencies: &TaskDependencies<F, FD, O>,
+        task_dependencies_core_ptr: Option<Arc<TaskDependenciesCore<F, FD, O>>>,
+        priority: Priority,
+    ) -> Waiting<O> {
+        // main thread only focus in swap queue, base on swap start
+        let (waiting_task_ptr, return_ptr) = self.create_waiting_task(
+            ExecTask::TaskWithDependencies(task),
+            task_dependencies_core_ptr,
+            Some(dependencies.waiting_list.clone()),
+            priority,
+            true,
+        );
         // check depencies
         if !dependencies
             .task_dependencies_ptr
@@ -271,7 +433,7 @@ where
                             .task_dependencies_ptr
                             .start
                             .store(null_mut(), Ordering::Release);
-                        self.spawn_task_with_dependencies_normal(waiting_task_ptr);
+                        self.push_to_swap_stack(waiting_task_ptr);
                     };
                 } else {
                     if !prev_waiting_task.is_null() {
@@ -288,30 +450,30 @@ where
                     }
                 }
             } else {
-                self.spawn_task_with_dependencies_normal(waiting_task_ptr);
+                self.push_to_swap_stack(waiting_task_ptr);
             }
         } else {
-            self.spawn_task_with_dependencies_normal(waiting_task_ptr);
+            self.push_to_swap_stack(waiting_task_ptr);
         };
 
-        Waiting {
-            data_ptr: return_ptr,
-        }
+        Waiting::init(return_ptr)
     }
 
-    fn spawn_task_with_dependencies_normal(&self, waiting_task_ptr: *mut WaitingTask<F, FD, O>) {
-        // swap start with new waiting task
-        let pre_start_task = self.swap_start.swap(waiting_task_ptr, Ordering::AcqRel);
-        if !pre_start_task.is_null() {
-            unsafe {
-                (*pre_start_task)
-                    .next
-                    .store(waiting_task_ptr, Ordering::Release);
-            }
-        } else {
-            // saving end waiting task for spanning validation in thread pool later
-            self.swap_end.store(waiting_task_ptr, Ordering::Release);
-        }
+    pub(crate) fn push_to_swap_stack(&self, waiting_task_ptr: *mut WaitingTask<F, FD, O>) {
+        self.push_list_to_swap_stack(waiting_task_ptr, waiting_task_ptr);
+    }
+
+    // end -> .. -> start, linked by next, every task of the list has the priority of `start`
+    fn push_list_to_swap_stack(
+        &self,
+        start: *mut WaitingTask<F, FD, O>,
+        end: *mut WaitingTask<F, FD, O>,
+    ) {
+        let priority = unsafe { (*start).priority };
+        self.lists[priority.index()].push_list_to_swap_stack(start, end);
+
+        // new task landed, wake a parked worker
+        self.idle_handler.wake_one();
     }
 
     pub fn spawn_task_dependencies<D, const NF: usize>(
@@ -321,134 +483,445 @@ where
     where
         D: ArrTaskDependenciesTrait<F, O, NF>,
     {
-        // create dependencies
-        let task_dependencies_core_ptr: &'static TaskDependenciesCore<F, FD, O> =
-            Box::leak(Box::new(TaskDependenciesCore::init(NF)));
+        self.spawn_task_dependencies_iter(dependencies.task_list())
+    }
+
+    pub fn spawn_task_dependencies_iter<I>(&self, dependencies: I) -> TaskDependencies<F, FD, O>
+    where
+        I: IntoIterator<Item = F>,
+    {
+        // the counter must be known before the first task is running
+        let task_list: Vec<F> = dependencies.into_iter().collect();
+
+        // create dependencies, nothing to wait for when empty
+        let task_dependencies_core_ptr = if task_list.is_empty() {
+            Arc::new(TaskDependenciesCore::blank())
+        } else {
+            Arc::new(TaskDependenciesCore::init(task_list.len()))
+        };
 
         // output
-        let mut waiting_output = Vec::with_capacity(NF);
+        let mut waiting_output = Vec::with_capacity(task_list.len());
 
         // task_dependencies
-        for task in dependencies.task_list() {
-            // update in_task handler
-            self.in_task.fetch_add(1, Ordering::SeqCst);
-            // create return_ptr
-            let return_ptr: &'static AtomicPtr<O> = Box::leak(Box::new(AtomicPtr::new(null_mut())));
+        for task in task_list {
+            let (waiting_task_ptr, return_ptr) = self.create_waiting_task(
+                ExecTask::Task(task),
+                Some(task_dependencies_core_ptr.clone()),
+                None,
+                Priority::Normal,
+                true,
+            );
 
-            // create waiting task
-            let waiting_task = WaitingTask {
-                id: self.id_counter.fetch_add(1, Ordering::Release),
-                task: ExecTask::Task(task),
-                next: AtomicPtr::new(ptr::null_mut()),
-                waiting_return_ptr: return_ptr,
-                task_dependencies_core_ptr,
-                task_dependencies_ptr: Box::leak(Box::new(vec![])),
-            };
+            self.push_to_swap_stack(waiting_task_ptr);
 
-            let waiting_task_ptr = Box::into_raw(Box::new(waiting_task));
+            waiting_output.push(Waiting::init(return_ptr));
+        }
 
-            // swap start with new waiting task
-            let pre_start_task = self.swap_start.swap(waiting_task_ptr, Ordering::AcqRel);
-            if !pre_start_task.is_null() {
-                unsafe {
-                    (*pre_start_task)
-                        .next
-                        .store(waiting_task_ptr, Ordering::Release);
-                }
-            } else {
-                // saving end waiting task for spanning validation in thread pool later
-                self.swap_end.store(waiting_task_ptr, Ordering::Release);
+        TaskDependencies {
+            waiting_list: Arc::new(waiting_output),
+            task_dependencies_ptr: task_dependencies_core_ptr,
+        }
+    }
+
+    pub fn spawn_graph(
+        self: &Arc<Self>,
+        graph: TaskGraph<F, FD, O>,
+    ) -> Result<Vec<Waiting<O>>, TaskGraphError> {
+        let order = graph.topological_order()?;
+
+        let mut nodes: Vec<_> = graph.nodes.into_iter().map(Some).collect();
+        let mut return_ptrs: Vec<Option<Arc<ReturnCell>>> = vec![None; nodes.len()];
+        // parents first, their return cells exist before the children
+        for index in order {
+            let node = nodes[index].take().unwrap();
+
+            let parents: Vec<Arc<ReturnCell>> = node
+                .parents
+                .iter()
+                .map(|parent| match parent {
+                    GraphParent::Node(parent) => return_ptrs[*parent].clone().unwrap(),
+                    GraphParent::Spawned(waiting) => waiting.data_ptr.clone(),
+                })
+                .collect();
+
+            // the spawned parents are released once the task holds its own handles
+            let return_ptr = self.spawn_exec_after(node.task, parents, node.priority);
+            if let Some(label) = node.label {
+                return_ptr.set_label(label);
             }
+            return_ptrs[index] = Some(return_ptr);
+        }
 
-            waiting_output.push(Waiting {
-                data_ptr: return_ptr,
-            });
+        Ok(return_ptrs
+            .into_iter()
+            .map(|return_ptr| Waiting::init(return_ptr.unwrap()))
+            .collect())
+    }
+
+    pub fn spawn_after(self: &Arc<Self>, dependencies: &[&Waiting<O>], task: FD) -> Waiting<O> {
+        self.spawn_after_with_priority(dependencies, task, Priority::Normal)
+    }
+
+    pub fn spawn_after_with_priority(
+        self: &Arc<Self>,
+        dependencies: &[&Waiting<O>],
+        task: FD,
+        priority: Priority,
+    ) -> Waiting<O> {
+        let parents: Vec<Arc<ReturnCell>> = dependencies
+            .iter()
+            .map(|waiting| waiting.data_ptr.clone())
+            .collect();
+
+        let return_ptr =
+            self.spawn_exec_after(ExecTask::TaskWithDependencies(task), parents, priority);
+
+        Waiting::init(return_ptr)
+    }
+
+    fn spawn_exec_after(
+        self: &Arc<Self>,
+        task: ExecTask<F, FD, O>,
+        parents: Vec<Arc<ReturnCell>>,
+        priority: Priority,
+    ) -> Arc<ReturnCell> {
+        // the outputs of the parents are the dependencies
+        let task_dependencies_ptr = if parents.is_empty() {
+            None
+        } else {
+            Some(Arc::new(
+                parents
+                    .iter()
+                    .map(|parent| Waiting::init(parent.clone()))
+                    .collect(),
+            ))
+        };
+        let (waiting_task_ptr, return_ptr) =
+            self.create_waiting_task(task, None, task_dependencies_ptr, priority, true);
+        self.spawn_after_return_cells(waiting_task_ptr, &parents);
+
+        return_ptr
+    }
+
+    // the waiting task is released once every parent has stored its output
+    fn spawn_after_return_cells(
+        self: &Arc<Self>,
+        waiting_task_ptr: *mut WaitingTask<F, FD, O>,
+        parents: &[Arc<ReturnCell>],
+    ) {
+        if parents.is_empty() {
+            self.push_to_swap_stack(waiting_task_ptr);
+            return;
         }
 
-        let waiting_output_leak: &'static mut Vec<Waiting<O>> = Box::leak(Box::new(waiting_output));
-        TaskDependencies {
-            waiting_list: waiting_output_leak,
-            task_dependencies_ptr: task_dependencies_core_ptr,
+        // one more than the parents, nothing is released before every parent is registered
+        let task_dependencies_core_ptr = Arc::new(TaskDependenciesCore::init(parents.len() + 1));
+        task_dependencies_core_ptr
+            .start
+            .store(waiting_task_ptr, Ordering::Release);
+        task_dependencies_core_ptr
+            .end
+            .store(waiting_task_ptr, Ordering::Release);
+
+        for parent in parents {
+            let list_core = self.clone();
+            let task_dependencies_core_ptr = task_dependencies_core_ptr.clone();
+            parent.on_ready(Box::new(move || {
+                list_core.release_dependencies(&task_dependencies_core_ptr)
+            }));
+        }
+
+        self.release_dependencies(&task_dependencies_core_ptr);
+    }
+
+    // count down, the last one moves the waiting tasks to the swap stack
+    fn release_dependencies(&self, task_dependencies_core_ptr: &TaskDependenciesCore<F, FD, O>) {
+        let counter = task_dependencies_core_ptr
+            .counter
+            .fetch_sub(1, Ordering::AcqRel);
+        if counter != 1 {
+            return;
+        }
+
+        task_dependencies_core_ptr
+            .done
+            .store(true, Ordering::Release);
+
+        let end = task_dependencies_core_ptr
+            .end
+            .swap(null_mut(), Ordering::AcqRel);
+        if !end.is_null() {
+            let start = task_dependencies_core_ptr
+                .start
+                .swap(null_mut(), Ordering::AcqRel);
+            self.push_list_to_swap_stack(start, end);
         }
     }
 
     pub fn spawn_task_fd(
         &self,
         task: FD,
-        task_dependencies_core_ptr: Option<&'static TaskDependenciesCore<F, FD, O>>,
+        task_dependencies_core_ptr: Option<Arc<TaskDependenciesCore<F, FD, O>>>,
     ) -> Waiting<O> {
         // main thread only focus in swap queue, base on swap start
+        let (waiting_task_ptr, return_ptr) = self.create_waiting_task(
+            ExecTask::TaskWithDependencies(task),
+            task_dependencies_core_ptr,
+            None,
+            Priority::Normal,
+            true,
+        );
+
+        self.push_to_swap_stack(waiting_task_ptr);
+
+        Waiting::init(return_ptr)
+    }
+
+    pub fn spawn_task(&self, task: F) -> Waiting<O> {
+        self.spawn_task_with_priority(task, Priority::Normal)
+    }
+
+    pub fn spawn_task_with_priority(&self, task: F, priority: Priority) -> Waiting<O> {
+        // main thread only focus in swap queue, base on swap start
+        let (waiting_task_ptr, return_ptr) =
+            self.create_waiting_task(ExecTask::Task(task), None, None, priority, true);
+
+        self.push_to_swap_stack(waiting_task_ptr);
+
+        Waiting::init(return_ptr)
+    }
+
+    // the caller pushes it to a list or runs it.
+    // not `counted` in in_task yet for a task that is counted once it is released
+    pub(crate) fn create_waiting_task(
+        &self,
+        task: ExecTask<F, FD, O>,
+        task_dependencies_core_ptr: Option<Arc<TaskDependenciesCore<F, FD, O>>>,
+        task_dependencies_ptr: Option<Arc<Vec<Waiting<O>>>>,
+        priority: Priority,
+        counted: bool,
+    ) -> (*mut WaitingTask<F, FD, O>, Arc<ReturnCell>) {
         // update in_task handler
-        self.in_task.fetch_add(1, Ordering::SeqCst);
+        if counted {
+            self.in_task.fetch_add(1, Ordering::SeqCst);
+        }
         // create return_ptr
-        let return_ptr: &'static AtomicPtr<O> = Box::leak(Box::new(AtomicPtr::new(null_mut())));
+        let id = self.id_counter.fetch_add(1, Ordering::Release);
+        let return_ptr = self.return_cell(id);
         // create waiting task
         let waiting_task = WaitingTask {
-            id: self.id_counter.fetch_add(1, Ordering::Release),
-            task: ExecTask::TaskWithDependencies(task),
-            next: AtomicPtr::new(ptr::null_mut()),
-            waiting_return_ptr: return_ptr,
-            task_dependencies_core_ptr: if let Some(ptr) = task_dependencies_core_ptr {
-                ptr
-            } else {
-                Box::leak(Box::new(TaskDependenciesCore::blank()))
-            },
-            task_dependencies_ptr: Box::leak(Box::new(Vec::new())),
+            task_dependencies_core_ptr,
+            task_dependencies_ptr,
+            ..WaitingTask::init(id, task, return_ptr.clone(), priority)
         };
 
-        let waiting_task_ptr = Box::into_raw(Box::new(waiting_task));
+        self.track(&waiting_task);
+        (Box::into_raw(Box::new(waiting_task)), return_ptr)
+    }
 
-        // swap start with new waiting task
-        let pre_start_task = self.swap_start.swap(waiting_task_ptr, Ordering::AcqRel);
-        if !pre_start_task.is_null() {
-            unsafe {
-                (*pre_start_task)
-                    .next
-                    .store(waiting_task_ptr, Ordering::Release);
+    pub fn spawn_future<T>(self: &Arc<Self>, future: T) -> Waiting<O>
+    where
+        T: Future<Output = O> + Send + 'static,
+    {
+        // create return_ptr
+        let id = self.id_counter.fetch_add(1, Ordering::Release);
+        let return_ptr = self.return_cell(id);
+        let future_core = Arc::new(FutureCore::init(
+            id,
+            Box::pin(future),
+            return_ptr.clone(),
+            self,
+        ));
+
+        let waiting_task_ptr = self.create_poll_task(future_core);
+        self.track(unsafe { &*waiting_task_ptr });
+        self.push_to_swap_stack(waiting_task_ptr);
+
+        Waiting::init(return_ptr)
+    }
+
+    // next poll of a spawned future, counted in in_task like any other task
+    pub(crate) fn create_poll_task(
+        &self,
+        future_core: Arc<FutureCore<F, FD, O>>,
+    ) -> *mut WaitingTask<F, FD, O> {
+        // update in_task handler
+        self.in_task.fetch_add(1, Ordering::SeqCst);
+        let id = future_core.id;
+        let return_ptr = future_core.return_ptr.clone();
+        let waiting_task = WaitingTask::init(
+            id,
+            ExecTask::Future(FutureTask { future_core }),
+            return_ptr,
+            Priority::Normal,
+        );
+
+        Box::into_raw(Box::new(waiting_task))
+    }
+
+    pub fn spawn_at(self: &Arc<Self>, deadline: Instant, task: F) -> Waiting<O> {
+        // counted now, join waits for delayed tasks too
+        let return_ptr = self.spawn_timer_task(deadline, task, true);
+
+        Waiting::init(return_ptr)
+    }
+
+    pub fn spawn_periodic(
+        self: &Arc<Self>,
+        interval: Duration,
+        mode: PeriodicMode,
+        task: Arc<dyn Fn() -> F + Send + Sync>,
+    ) -> PeriodicHandle {
+        assert!(!interval.is_zero(), "interval must be greater than zero");
+
+        let periodic_core = Arc::new(PeriodicCore::init());
+        {
+            let mut periodic_tasks = self.periodic_tasks.lock().unwrap();
+            // forget stopped tasks before growing
+            if periodic_tasks.len() == periodic_tasks.capacity() {
+                periodic_tasks.retain(|periodic_core| {
+                    periodic_core
+                        .upgrade()
+                        .is_some_and(|periodic_core| !periodic_core.is_stopped())
+                });
             }
-        } else {
-            // saving end waiting task for spanning validation in thread pool later
-            self.swap_end.store(waiting_task_ptr, Ordering::Release);
+            periodic_tasks.push(Arc::downgrade(&periodic_core));
         }
 
-        Waiting {
-            data_ptr: return_ptr,
-        }
+        self.spawn_periodic_run(
+            Instant::now() + interval,
+            interval,
+            mode,
+            task,
+            periodic_core.clone(),
+        );
+
+        PeriodicHandle { periodic_core }
     }
 
-    pub fn spawn_task(&self, task: F) -> Waiting<O> {
-        // main thread only focus in swap queue, base on swap start
-        // update in_task handler
-        self.in_task.fetch_add(1, Ordering::SeqCst);
-        // create return_ptr
-        let return_ptr: &'static AtomicPtr<O> = Box::leak(Box::new(AtomicPtr::new(null_mut())));
-        // create waiting task
-        let waiting_task = WaitingTask {
-            id: self.id_counter.fetch_add(1, Ordering::Release),
-            task: ExecTask::Task(task),
-            next: AtomicPtr::new(ptr::null_mut()),
-            waiting_return_ptr: return_ptr,
-            task_dependencies_core_ptr: Box::leak(Box::new(TaskDependenciesCore::blank())),
-            task_dependencies_ptr: Box::leak(Box::new(Vec::new())),
-        };
+    // one run, the next one is scheduled once it is done
+    fn spawn_periodic_run(
+        self: &Arc<Self>,
+        deadline: Instant,
+        interval: Duration,
+        mode: PeriodicMode,
+        task: Arc<dyn Fn() -> F + Send + Sync>,
+        periodic_core: Arc<PeriodicCore>,
+    ) {
+        if periodic_core.is_stopped() {
+            return;
+        }
 
-        let waiting_task_ptr = Box::into_raw(Box::new(waiting_task));
+        // not counted until released, join does not wait for the next run
+        let return_ptr = self.spawn_timer_task(deadline, task(), false);
+        periodic_core.set_pending(&return_ptr);
+
+        let list_core = self.clone();
+        let run_ptr = Arc::downgrade(&return_ptr);
+        return_ptr.on_ready(Box::new(move || {
+            if run_ptr
+                .upgrade()
+                .is_some_and(|return_ptr| return_ptr.state() == CellState::Done)
+            {
+                periodic_core.add_run();
+            }
 
-        // swap start with new waiting task
-        let pre_start_task = self.swap_start.swap(waiting_task_ptr, Ordering::AcqRel);
-        if !pre_start_task.is_null() {
-            unsafe {
-                (*pre_start_task)
-                    .next
-                    .store(waiting_task_ptr, Ordering::Release);
+            let next_deadline = mode.next_deadline(interval, deadline, Instant::now());
+            list_core.spawn_periodic_run(next_deadline, interval, mode, task, periodic_core);
+        }));
+    }
+
+    fn spawn_timer_task(
+        self: &Arc<Self>,
+        deadline: Instant,
+        task: F,
+        counted: bool,
+    ) -> Arc<ReturnCell> {
+        let (waiting_task_ptr, return_ptr) =
+            self.create_waiting_task(ExecTask::Task(task), None, None, Priority::Normal, counted);
+        let id = return_ptr.id();
+
+        // the timer thread keeps the list core alive until stop_timer
+        let list_core = self.clone();
+        self.timer
+            .schedule(deadline, waiting_task_ptr, counted, move || {
+                list_core.timer.running(|waiting_task_ptr, counted| {
+                    list_core.release_timer_task(waiting_task_ptr, counted)
+                })
+            });
+
+        // cancelled, released at once so join does not wait for the deadline
+        let list_core = Arc::downgrade(self);
+        let cancelled_ptr = Arc::downgrade(&return_ptr);
+        return_ptr.on_ready(Box::new(move || {
+            let cancelled = cancelled_ptr
+                .upgrade()
+                .is_some_and(|return_ptr| return_ptr.state() == CellState::Cancelled);
+            if !cancelled {
+                return;
             }
-        } else {
-            // saving end waiting task for spanning validation in thread pool later
-            self.swap_end.store(waiting_task_ptr, Ordering::Release);
+            if let Some(list_core) = list_core.upgrade()
+                && let Some((waiting_task_ptr, counted)) = list_core.timer.remove(id)
+            {
+                list_core.release_timer_task(waiting_task_ptr, counted);
+            }
+        }));
+
+        return_ptr
+    }
+
+    // due or cancelled, a worker finishes it
+    fn release_timer_task(&self, waiting_task_ptr: *mut WaitingTask<F, FD, O>, counted: bool) {
+        if !counted {
+            self.in_task.fetch_add(1, Ordering::SeqCst);
         }
+        self.push_to_swap_stack(waiting_task_ptr)
+    }
 
-        Waiting {
-            data_ptr: return_ptr,
+    pub(crate) fn stop_periodic(&self) {
+        let periodic_tasks = std::mem::take(&mut *self.periodic_tasks.lock().unwrap());
+        for periodic_core in periodic_tasks.iter().filter_map(Weak::upgrade) {
+            periodic_core.stop();
         }
     }
+
+    pub(crate) fn stop_timer(&self) {
+        self.timer.stop();
+    }
+}
+
+impl<F, FD, O> ListCore<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    // run `task` on the workers without an output of type O, `task` stores its own output
+    pub(crate) fn spawn_erased(self: &Arc<Self>, task: Continuation) -> Arc<ReturnCell> {
+        self.spawn_exec_after(
+            ExecTask::Continuation(ContinuationTask::init(task)),
+            Vec::new(),
+            Priority::Normal,
+        )
+    }
+}
+
+impl<F, FD, O> Spawner for ListCore<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    fn spawn_continuation(
+        self: Arc<Self>,
+        parent: Arc<ReturnCell>,
+        continuation: Continuation,
+    ) -> Arc<ReturnCell> {
+        self.spawn_exec_after(
+            ExecTask::Continuation(ContinuationTask::init(continuation)),
+            vec![parent],
+            Priority::Normal,
+        )
+    }
 }
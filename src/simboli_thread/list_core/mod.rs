@@ -1,7 +1,12 @@
 mod list_core;
 pub use list_core::*;
 
+mod priority;
+pub use priority::Priority;
+
 mod task_list;
 
+mod timer;
+
 mod wait;
 pub use wait::{ArrTaskDependenciesTrait, *};
@@ -0,0 +1,185 @@
+use std::{
+    hint::spin_loop,
+    ptr::{self, null_mut},
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::{
+    OutputTrait, TaskOnceTrait, TaskWithDependenciesOnceTrait, WaitingTask,
+    simboli_thread::list_core::task_list::TaskList,
+};
+
+/// priority of a task, representative threads take higher levels first and idle threads
+/// prefer stealing high priority tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl Priority {
+    // index of the priority list, highest first
+    pub(crate) const LEVELS: [Priority; 3] = [Priority::High, Priority::Normal, Priority::Low];
+
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+
+    pub(crate) fn from_index(index: usize) -> Priority {
+        Self::LEVELS[index]
+    }
+}
+
+// primary list and swap stack of one priority level
+pub(crate) struct PriorityList<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    // primary Stack
+    start: AtomicPtr<WaitingTask<F, FD, O>>,
+    end: AtomicPtr<WaitingTask<F, FD, O>>,
+
+    // Swap Stack
+    swap_start: AtomicPtr<WaitingTask<F, FD, O>>,
+    swap_end: AtomicPtr<WaitingTask<F, FD, O>>,
+}
+
+impl<F, FD, O> PriorityList<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    pub(crate) fn init() -> PriorityList<F, FD, O> {
+        Self {
+            start: AtomicPtr::new(ptr::null_mut()),
+            end: AtomicPtr::new(ptr::null_mut()),
+            swap_start: AtomicPtr::new(ptr::null_mut()),
+            swap_end: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    pub(crate) fn is_primary_list_empty(&self) -> bool {
+        self.end.load(Ordering::Acquire).is_null()
+    }
+
+    pub(crate) fn is_swap_list_empty(&self) -> bool {
+        self.swap_start.load(Ordering::Acquire).is_null()
+            && self.swap_end.load(Ordering::Acquire).is_null()
+    }
+
+    pub(crate) fn get_waiting_task_from_primary_stack(
+        &self,
+        len: usize,
+    ) -> Result<TaskList<F, FD, O>, &str> {
+        let start_waiting_task = self.start.load(Ordering::Acquire);
+
+        // scanning start from "end"
+        let mut list_task = Vec::with_capacity(len);
+        for _ in 0..len {
+            list_task.push(AtomicPtr::new(null_mut()));
+        }
+        let mut count: u64 = 0;
+        unsafe {
+            loop {
+                let waiting_task = self.end.load(Ordering::Acquire);
+                if waiting_task.is_null() {
+                    return Err("Primary list empty");
+                }
+
+                let next_waiting_task = (*waiting_task).next.load(Ordering::Acquire);
+                if next_waiting_task.is_null() {
+                    // check, is this last task?
+                    if start_waiting_task == waiting_task {
+                        // this last task
+                        // store the task
+                        // // start from bottom
+                        list_task[(len - 1) - count as usize] = AtomicPtr::new(waiting_task);
+                        // update start
+                        self.start.store(null_mut(), Ordering::Release);
+                        // update end
+                        self.end.store(null_mut(), Ordering::Release);
+                        // update counter
+                        count += 1;
+                        break;
+                    } else {
+                        // waiting
+                        spin_loop();
+                        continue;
+                    };
+                }
+
+                // store the task
+                // // start from bottom
+
+                list_task[(len - 1) - count as usize] = AtomicPtr::new(waiting_task);
+                // update end
+                self.end.store(next_waiting_task, Ordering::Release);
+                count += 1;
+
+                if count >= len as u64 {
+                    break;
+                }
+            }
+        }
+
+        let list_task = TaskList {
+            list: list_task.into_boxed_slice(),
+            top: (len as u64) - count,
+            bottom: len as u64,
+            priority: Priority::Normal,
+        };
+
+        Ok(list_task)
+    }
+
+    // end -> .. -> start, linked by next
+    pub(crate) fn insert_list_to_primary(
+        &self,
+        start: *mut WaitingTask<F, FD, O>,
+        end: *mut WaitingTask<F, FD, O>,
+    ) {
+        let prev_start = self.start.swap(start, Ordering::AcqRel);
+        if !prev_start.is_null() {
+            unsafe {
+                (*prev_start).next.store(end, Ordering::Release);
+            }
+        } else {
+            self.end.store(end, Ordering::Release);
+        }
+    }
+
+    pub(crate) fn swap_to_primary(&self) -> Result<(), &str> {
+        let end = self.swap_end.swap(null_mut(), Ordering::AcqRel);
+        if !end.is_null() {
+            let start = self.swap_start.swap(null_mut(), Ordering::AcqRel);
+            self.start.store(start, Ordering::Release);
+            self.end.store(end, Ordering::Release);
+            Ok(())
+        } else {
+            Err("SWAP STACK EMPTY")
+        }
+    }
+
+    // end -> .. -> start, linked by next
+    pub(crate) fn push_list_to_swap_stack(
+        &self,
+        start: *mut WaitingTask<F, FD, O>,
+        end: *mut WaitingTask<F, FD, O>,
+    ) {
+        // swap start with new waiting task
+        let pre_start_task = self.swap_start.swap(start, Ordering::AcqRel);
+        if !pre_start_task.is_null() {
+            unsafe {
+                (*pre_start_task).next.store(end, Ordering::Release);
+            }
+        } else {
+            // saving end waiting task for spanning validation in thread pool later
+            self.swap_end.store(end, Ordering::Release);
+        }
+    }
+}
@@ -1,15 +1,15 @@
 use std::sync::atomic::AtomicPtr;
 
-use crate::{OutputTrait, TaskTrait, TaskWithDependenciesTrait, WaitingTask};
+use crate::{OutputTrait, Priority, TaskOnceTrait, TaskWithDependenciesOnceTrait, WaitingTask};
 
-pub struct TaskList<F, FD, O, const N: usize>
+pub struct TaskList<F, FD, O>
 where
-    F: TaskTrait<O> + Send + 'static,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
-    pub(crate) list: [AtomicPtr<WaitingTask<F, FD, O>>; N],
-    pub(crate) count: u64,
+    pub(crate) list: Box<[AtomicPtr<WaitingTask<F, FD, O>>]>,
     pub(crate) top: u64,
     pub(crate) bottom: u64,
+    pub(crate) priority: Priority,
 }
@@ -0,0 +1,232 @@
+use std::{
+    cmp::Ordering as CmpOrdering,
+    collections::BinaryHeap,
+    ptr::null_mut,
+    sync::{
+        Condvar, Mutex,
+        atomic::{AtomicBool, AtomicPtr, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+use crate::{OutputTrait, TaskOnceTrait, TaskWithDependenciesOnceTrait, WaitingTask};
+
+// waiting task released to the swap stack at deadline
+struct TimerEntry<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    deadline: Instant,
+    // task id, same deadline runs in spawning order
+    id: u64,
+    waiting_task_ptr: AtomicPtr<WaitingTask<F, FD, O>>,
+    // already counted in in_task, otherwise counted when released
+    counted: bool,
+}
+
+impl<F, FD, O> PartialEq for TimerEntry<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline && self.id == other.id
+    }
+}
+
+impl<F, FD, O> Eq for TimerEntry<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+}
+
+impl<F, FD, O> PartialOrd for TimerEntry<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F, FD, O> Ord for TimerEntry<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    // reversed, the earliest deadline is on top of the heap
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        other
+            .deadline
+            .cmp(&self.deadline)
+            .then_with(|| other.id.cmp(&self.id))
+    }
+}
+
+// delayed tasks of the thread pool, the timer thread is spawned with the first delayed task
+pub(crate) struct Timer<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    entries: Mutex<BinaryHeap<TimerEntry<F, FD, O>>>,
+    condvar: Condvar,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    join_flag: AtomicBool,
+    thread_name: Option<String>,
+}
+
+impl<F, FD, O> Timer<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    pub(crate) fn init(thread_name: Option<String>) -> Timer<F, FD, O> {
+        Self {
+            entries: Mutex::new(BinaryHeap::new()),
+            condvar: Condvar::new(),
+            handle: Mutex::new(None),
+            join_flag: AtomicBool::new(false),
+            thread_name,
+        }
+    }
+
+    // `running` is the body of the timer thread, spawned once
+    pub(crate) fn schedule(
+        &self,
+        deadline: Instant,
+        waiting_task_ptr: *mut WaitingTask<F, FD, O>,
+        counted: bool,
+        running: impl FnOnce() + Send + 'static,
+    ) {
+        let id = unsafe { (*waiting_task_ptr).id };
+        {
+            let mut entries = self.entries.lock().unwrap();
+            // stopped, a periodic task finishing after join
+            if self.join_flag.load(Ordering::Acquire) {
+                drop(unsafe { Box::from_raw(waiting_task_ptr) });
+                return;
+            }
+            entries.push(TimerEntry {
+                deadline,
+                id,
+                waiting_task_ptr: AtomicPtr::new(waiting_task_ptr),
+                counted,
+            });
+        }
+
+        let mut handle = self.handle.lock().unwrap();
+        if handle.is_none() {
+            let mut builder = thread::Builder::new();
+            if let Some(thread_name) = &self.thread_name {
+                builder = builder.name(format!("{}-timer", thread_name));
+            }
+            *handle = Some(
+                builder
+                    .spawn(running)
+                    .expect("failed to spawn thread pool timer thread"),
+            );
+        }
+
+        // the new task can be the earliest one
+        self.condvar.notify_one();
+    }
+
+    // release every due task, until the timer is stopped
+    pub(crate) fn running(&self, release: impl Fn(*mut WaitingTask<F, FD, O>, bool)) {
+        let mut entries = self.entries.lock().unwrap();
+        loop {
+            if self.join_flag.load(Ordering::Acquire) {
+                break;
+            }
+
+            let now = Instant::now();
+            while entries.peek().is_some_and(|entry| entry.deadline <= now) {
+                let entry = entries.pop().unwrap();
+                release(
+                    entry.waiting_task_ptr.swap(null_mut(), Ordering::AcqRel),
+                    entry.counted,
+                );
+            }
+
+            entries = match entries.peek() {
+                Some(entry) => {
+                    let timeout = entry.deadline.saturating_duration_since(now);
+                    self.condvar.wait_timeout(entries, timeout).unwrap().0
+                }
+                None => self.condvar.wait(entries).unwrap(),
+            };
+        }
+    }
+
+    // take the task of `id` out before its deadline, `None` if it is already released
+    pub(crate) fn remove(&self, id: u64) -> Option<(*mut WaitingTask<F, FD, O>, bool)> {
+        let mut entries = self.entries.lock().unwrap();
+        // stopped, the entries are dropped by stop
+        if self.join_flag.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let mut removed = None;
+        entries.retain(|entry| {
+            if entry.id != id {
+                return true;
+            }
+            removed = Some((
+                entry.waiting_task_ptr.swap(null_mut(), Ordering::AcqRel),
+                entry.counted,
+            ));
+            false
+        });
+
+        removed.filter(|(waiting_task_ptr, _)| !waiting_task_ptr.is_null())
+    }
+
+    // stop and join the timer thread, tasks not released yet are dropped
+    pub(crate) fn stop(&self) {
+        {
+            let _entries = self.entries.lock().unwrap();
+            self.join_flag.store(true, Ordering::Release);
+            self.condvar.notify_all();
+        }
+
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.join().unwrap();
+        }
+
+        // the next runs of periodic tasks
+        let entries = std::mem::take(&mut *self.entries.lock().unwrap());
+        Self::drop_entries(entries);
+    }
+
+    fn drop_entries(entries: BinaryHeap<TimerEntry<F, FD, O>>) {
+        for entry in entries {
+            let waiting_task_ptr = entry.waiting_task_ptr.swap(null_mut(), Ordering::AcqRel);
+            if !waiting_task_ptr.is_null() {
+                drop(unsafe { Box::from_raw(waiting_task_ptr) });
+            }
+        }
+    }
+}
+
+impl<F, FD, O> Drop for Timer<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    fn drop(&mut self) {
+        Self::drop_entries(std::mem::take(self.entries.get_mut().unwrap()));
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::{
+    Arc, Mutex, Weak,
+    atomic::{AtomicBool, Ordering},
+};
+
+use crate::{Waiting, simboli_thread::list_core::wait::ReturnCell};
+
+struct TokenCore {
+    cancelled: AtomicBool,
+    // registered tasks
+    tasks: Mutex<Vec<Weak<ReturnCell>>>,
+}
+
+/// shared cancellation, every clone cancels the same tasks.
+/// tasks registered with `register` are skipped if they have not started, running tasks
+/// can poll `is_cancelled` and return early
+#[derive(Clone)]
+pub struct CancellationToken {
+    token_core: Arc<TokenCore>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        Self {
+            token_core: Arc::new(TokenCore {
+                cancelled: AtomicBool::new(false),
+                tasks: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token_core.cancelled.load(Ordering::Acquire)
+    }
+
+    /// cancel every registered task that has not started, tasks waiting on them are cancelled too
+    pub fn cancel(&self) {
+        self.token_core.cancelled.store(true, Ordering::Release);
+
+        let tasks = std::mem::take(&mut *self.token_core.tasks.lock().unwrap());
+        for return_ptr in tasks.iter().filter_map(Weak::upgrade) {
+            return_ptr.cancel();
+        }
+    }
+
+    /// cancel the task of `waiting` with this token, directly if the token is already cancelled
+    pub fn register<O>(&self, waiting: &Waiting<O>) {
+        {
+            let mut tasks = self.token_core.tasks.lock().unwrap();
+            if !self.is_cancelled() {
+                // forget finished tasks before growing
+                if tasks.len() == tasks.capacity() {
+                    tasks.retain(|return_ptr| return_ptr.strong_count() > 0);
+                }
+                tasks.push(Arc::downgrade(&waiting.data_ptr));
+                return;
+            }
+        }
+
+        waiting.data_ptr.cancel();
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
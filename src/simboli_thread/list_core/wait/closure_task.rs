@@ -0,0 +1,127 @@
+use std::cell::Cell;
+
+use crate::{
+    AnyOutput, ArrTaskDependenciesTrait, ArrTaskDependenciesWithDependenciesTrait, OutputTrait,
+    TaskTrait, TaskWithDependenciesTrait, Waiting,
+};
+
+pub type Closure<O> = Box<dyn FnOnce() -> O + Send>;
+pub type ClosureWithDependencies<O> = Box<dyn FnOnce(&[Waiting<O>]) -> O + Send>;
+
+enum ClosureKind<O>
+where
+    O: 'static,
+{
+    Exec(Closure<O>),
+    ExecWithDependencies(ClosureWithDependencies<O>),
+}
+
+/// task made from a closure, used by `SimboliThread::closures`.
+/// the thread pool runs it once through `exec_once`, calling `exec` yourself a second time panics
+pub struct ClosureTask<O>
+where
+    O: 'static,
+{
+    // TaskOnceTrait can not be implemented here directly, it would overlap with the impls for
+    // every TaskTrait (a downstream crate may implement TaskTrait<T> for ClosureTask<T>).
+    // exec only borrows the task, the closure is taken out of the cell
+    closure: Cell<Option<ClosureKind<O>>>,
+    with_dependencies: bool,
+}
+
+impl<O> ClosureTask<O>
+where
+    O: 'static,
+{
+    pub fn new<T>(f: T) -> ClosureTask<O>
+    where
+        T: FnOnce() -> O + Send + 'static,
+    {
+        Self {
+            closure: Cell::new(Some(ClosureKind::Exec(Box::new(f)))),
+            with_dependencies: false,
+        }
+    }
+
+    pub fn with_dependencies<T>(f: T) -> ClosureTask<O>
+    where
+        T: FnOnce(&[Waiting<O>]) -> O + Send + 'static,
+    {
+        Self {
+            closure: Cell::new(Some(ClosureKind::ExecWithDependencies(Box::new(f)))),
+            with_dependencies: true,
+        }
+    }
+
+    fn exec_closure(&self, dependencies: &[Waiting<O>]) -> O {
+        match self.closure.take() {
+            Some(ClosureKind::Exec(f)) => f(),
+            Some(ClosureKind::ExecWithDependencies(f)) => f(dependencies),
+            None => panic!("closure task executed twice"),
+        }
+    }
+}
+
+impl ClosureTask<AnyOutput> {
+    /// closure with its own output type, read it back with `Waiting::downcast`
+    pub fn typed<T, R>(f: T) -> ClosureTask<AnyOutput>
+    where
+        T: FnOnce() -> R + Send + 'static,
+        R: Send + Sync + 'static,
+    {
+        Self::new(move || Box::new(f()) as AnyOutput)
+    }
+
+    /// closure with its own output type, read it back with `Waiting::downcast`.
+    /// the output of each dependency is read with `Waiting::downcast_ref`
+    pub fn typed_with_dependencies<T, R>(f: T) -> ClosureTask<AnyOutput>
+    where
+        T: FnOnce(&[Waiting<AnyOutput>]) -> R + Send + 'static,
+        R: Send + Sync + 'static,
+    {
+        Self::with_dependencies(move |dependencies: &[Waiting<AnyOutput>]| {
+            Box::new(f(dependencies)) as AnyOutput
+        })
+    }
+}
+
+impl<O> TaskTrait<O> for ClosureTask<O>
+where
+    O: OutputTrait + 'static,
+{
+    fn exec(&self) -> O {
+        self.exec_closure(&[])
+    }
+}
+
+impl<O> TaskWithDependenciesTrait<O> for ClosureTask<O>
+where
+    O: OutputTrait + 'static + Send,
+{
+    fn exec(&self, dependencies: &[Waiting<O>]) -> O {
+        self.exec_closure(dependencies)
+    }
+
+    fn is_with_dependencies(&self) -> bool {
+        self.with_dependencies
+    }
+}
+
+impl<O, const NF: usize> ArrTaskDependenciesTrait<ClosureTask<O>, O, NF> for [ClosureTask<O>; NF]
+where
+    O: OutputTrait + 'static + Send,
+{
+    fn task_list(self) -> [ClosureTask<O>; NF] {
+        self
+    }
+}
+
+impl<O, const NF: usize> ArrTaskDependenciesWithDependenciesTrait<ClosureTask<O>, O, NF>
+    for [ClosureTask<O>; NF]
+where
+    O: OutputTrait + 'static + Send,
+{
+    fn task_list(self) -> [ClosureTask<O>; NF] {
+        self
+    }
+}
@@ -0,0 +1,41 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+};
+
+use crate::{TaskPanic, WaitingError, simboli_thread::list_core::wait::ReturnCell};
+
+// reads the output of the parent and stores its own output in the given return cell
+pub(crate) type Continuation = Box<dyn FnOnce(&ReturnCell) + Send>;
+
+// list core of a task, type erased so `Waiting` can spawn its continuations
+pub(crate) trait Spawner: Send + Sync {
+    // `continuation` runs on the thread pool once `parent` has stored its output
+    fn spawn_continuation(
+        self: Arc<Self>,
+        parent: Arc<ReturnCell>,
+        continuation: Continuation,
+    ) -> Arc<ReturnCell>;
+}
+
+/// continuation of a `Waiting`, from `Waiting::then`, `Waiting::map` or `Waiting::and_then`
+pub struct ContinuationTask {
+    continuation: Continuation,
+}
+
+impl ContinuationTask {
+    pub(crate) fn init(continuation: Continuation) -> ContinuationTask {
+        Self { continuation }
+    }
+
+    pub(crate) fn run(self, id: u64, return_ptr: &ReturnCell) {
+        // catch panic, keep the worker alive
+        if let Err(payload) =
+            panic::catch_unwind(AssertUnwindSafe(|| (self.continuation)(return_ptr)))
+        {
+            return_ptr.store::<()>(Err(WaitingError::Panicked(TaskPanic::from_payload(
+                id, payload,
+            ))));
+        }
+    }
+}
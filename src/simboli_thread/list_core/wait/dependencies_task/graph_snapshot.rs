@@ -0,0 +1,83 @@
+use std::fmt::{self, Display};
+
+/// state of a task in a `GraphSnapshot`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskState {
+    /// some dependencies are not done
+    Waiting,
+    /// ready, waiting for a thread
+    Queued,
+    Running,
+    Done,
+    Cancelled,
+}
+
+impl Display for TaskState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskState::Waiting => write!(f, "waiting"),
+            TaskState::Queued => write!(f, "queued"),
+            TaskState::Running => write!(f, "running"),
+            TaskState::Done => write!(f, "done"),
+            TaskState::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// task in a `GraphSnapshot`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotTask {
+    pub id: u64,
+    pub label: Option<String>,
+    pub state: TaskState,
+    /// id of the tasks this task waits on
+    pub dependencies: Vec<u64>,
+}
+
+/// pending tasks and their dependencies at one point in time, from `SimboliThread::graph_snapshot`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphSnapshot {
+    pub tasks: Vec<SnapshotTask>,
+}
+
+impl GraphSnapshot {
+    /// render as Graphviz DOT, an edge goes from a dependency to the task waiting on it
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph simboli_thread {\n    node [shape=box, style=filled];\n");
+
+        for task in self.tasks.iter() {
+            let label = match &task.label {
+                Some(label) => format!("{} {}\\n{}", task.id, escape(label), task.state),
+                None => format!("{}\\n{}", task.id, task.state),
+            };
+            let color = match task.state {
+                TaskState::Waiting => "lightgrey",
+                TaskState::Queued => "lightblue",
+                TaskState::Running => "gold",
+                TaskState::Done => "palegreen",
+                TaskState::Cancelled => "salmon",
+            };
+            dot.push_str(&format!(
+                "    t{} [label=\"{}\", fillcolor={}];\n",
+                task.id, label, color
+            ));
+        }
+
+        for task in self.tasks.iter() {
+            for dependency in task.dependencies.iter() {
+                dot.push_str(&format!("    t{} -> t{};\n", dependency, task.id));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -3,3 +3,10 @@ pub use task_dependencies::{
     ArrTaskDependenciesTrait, ArrTaskDependenciesWithDependenciesTrait, TaskDependencies,
     TaskDependenciesCore,
 };
+
+mod task_graph;
+pub(crate) use task_graph::GraphParent;
+pub use task_graph::{TaskGraph, TaskNode};
+
+mod graph_snapshot;
+pub use graph_snapshot::*;
@@ -1,21 +1,23 @@
 use std::{
     ptr::null_mut,
-    sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicPtr, AtomicUsize},
+    },
 };
 
 use crate::{
-    OutputTrait, TaskTrait, TaskWithDependenciesTrait, WaitingTask,
+    OutputTrait, TaskOnceTrait, TaskWithDependenciesOnceTrait, WaitingTask,
     simboli_thread::list_core::Waiting,
 };
 
 // will be shared. to Waiting<O> and WaitingTask<F, O>
 pub struct TaskDependenciesCore<F, FD, O>
 where
-    F: TaskTrait<O> + Send + 'static,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
-    pub(crate) status: bool,
     pub(crate) done: AtomicBool,
     pub(crate) counter: AtomicUsize,
     pub(crate) start: AtomicPtr<WaitingTask<F, FD, O>>, // default null, will capture the task need this task output
@@ -24,13 +26,12 @@ where
 
 impl<F, FD, O> TaskDependenciesCore<F, FD, O>
 where
-    F: TaskTrait<O> + Send + 'static,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
     pub fn init(counter: usize) -> TaskDependenciesCore<F, FD, O> {
         Self {
-            status: true,
             done: AtomicBool::new(false),
             counter: AtomicUsize::new(counter),
             start: AtomicPtr::new(null_mut()),
@@ -38,10 +39,10 @@ where
         }
     }
 
+    /// already done, tasks waiting on a blank core are spawned directly
     pub fn blank() -> TaskDependenciesCore<F, FD, O> {
         Self {
-            status: false,
-            done: AtomicBool::new(false),
+            done: AtomicBool::new(true),
             counter: AtomicUsize::new(0),
             start: AtomicPtr::new(null_mut()),
             end: AtomicPtr::new(null_mut()),
@@ -51,31 +52,31 @@ where
 
 pub struct TaskDependencies<F, FD, O>
 where
-    F: TaskTrait<O> + Send + 'static,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
-    pub(crate) task_dependencies_ptr: &'static TaskDependenciesCore<F, FD, O>,
-    pub waiting_list: &'static Vec<Waiting<O>>,
+    pub(crate) task_dependencies_ptr: Arc<TaskDependenciesCore<F, FD, O>>,
+    pub waiting_list: Arc<Vec<Waiting<O>>>,
 }
 
 impl<F, FD, O> TaskDependencies<F, FD, O>
 where
-    F: TaskTrait<O> + Send + 'static,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
     pub fn blank() -> TaskDependencies<F, FD, O> {
         Self {
-            task_dependencies_ptr: Box::leak(Box::new(TaskDependenciesCore::blank())),
-            waiting_list: Box::leak(Box::new(vec![])),
+            task_dependencies_ptr: Arc::new(TaskDependenciesCore::blank()),
+            waiting_list: Arc::new(Vec::new()),
         }
     }
 }
 
 pub trait ArrTaskDependenciesTrait<F, O, const NF: usize>
 where
-    F: TaskTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait,
 {
     fn task_list(self) -> [F; NF];
@@ -83,7 +84,7 @@ where
 
 pub trait ArrTaskDependenciesWithDependenciesTrait<FD, O, const NF: usize>
 where
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
     fn task_list(self) -> [FD; NF];
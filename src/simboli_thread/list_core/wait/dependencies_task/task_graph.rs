@@ -0,0 +1,211 @@
+use crate::{
+    ExecTask, OutputTrait, Priority, TaskDependencies, TaskGraphError, TaskOnceTrait,
+    TaskWithDependenciesOnceTrait, Waiting,
+};
+
+/// node of a `TaskGraph`, the index of its `Waiting` in the output of `SimboliThread::spawn_graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskNode(pub(crate) usize);
+
+impl TaskNode {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+pub(crate) enum GraphParent<O>
+where
+    O: 'static,
+{
+    // node of the same graph
+    Node(usize),
+    // task spawned outside the graph, the handle keeps its output from being collected
+    Spawned(Waiting<O>),
+}
+
+pub(crate) struct GraphNode<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    pub(crate) task: ExecTask<F, FD, O>,
+    pub(crate) parents: Vec<GraphParent<O>>,
+    pub(crate) label: Option<String>,
+    pub(crate) priority: Priority,
+}
+
+/// tasks with arbitrary dependencies between them, spawned at once with `SimboliThread::spawn_graph`.
+/// a task receives the outputs of its dependencies in the order they were added
+pub struct TaskGraph<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    pub(crate) nodes: Vec<GraphNode<F, FD, O>>,
+}
+
+impl<F, FD, O> TaskGraph<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    pub fn new() -> TaskGraph<F, FD, O> {
+        Self { nodes: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// task without dependency outputs, it can still wait on other tasks
+    pub fn add_task(&mut self, task: F) -> TaskNode {
+        self.add_node(ExecTask::Task(task))
+    }
+
+    pub fn add_task_with_dependencies(&mut self, task: FD) -> TaskNode {
+        self.add_node(ExecTask::TaskWithDependencies(task))
+    }
+
+    fn add_node(&mut self, task: ExecTask<F, FD, O>) -> TaskNode {
+        self.nodes.push(GraphNode {
+            task,
+            parents: Vec::new(),
+            label: None,
+            priority: Priority::Normal,
+        });
+        TaskNode(self.nodes.len() - 1)
+    }
+
+    /// label of the task in `GraphSnapshot`, panics if `node` is not part of this graph
+    pub fn set_label(
+        &mut self,
+        node: TaskNode,
+        label: impl Into<String>,
+    ) -> &mut TaskGraph<F, FD, O> {
+        match self.nodes.get_mut(node.0) {
+            Some(graph_node) => graph_node.label = Some(label.into()),
+            None => panic!("{}", TaskGraphError::UnknownNode(node)),
+        }
+        self
+    }
+
+    /// priority of the task once its dependencies are done, panics if `node` is not part of this graph
+    pub fn set_priority(&mut self, node: TaskNode, priority: Priority) -> &mut TaskGraph<F, FD, O> {
+        match self.nodes.get_mut(node.0) {
+            Some(graph_node) => graph_node.priority = priority,
+            None => panic!("{}", TaskGraphError::UnknownNode(node)),
+        }
+        self
+    }
+
+    /// `node` runs after `dependency` is done.
+    /// the `depends_on` methods panic if `node` is not part of this graph
+    pub fn depends_on(&mut self, node: TaskNode, dependency: TaskNode) -> &mut TaskGraph<F, FD, O>
+    where
+        O: Sync,
+    {
+        self.parents(node).push(GraphParent::Node(dependency.0));
+        self
+    }
+
+    /// `node` runs after the task of `waiting`, spawned outside the graph, is done.
+    /// `collect` of `waiting` waits until the graph is spawned and `node` is done
+    pub fn depends_on_waiting(
+        &mut self,
+        node: TaskNode,
+        waiting: &Waiting<O>,
+    ) -> &mut TaskGraph<F, FD, O>
+    where
+        O: Sync,
+    {
+        self.parents(node).push(GraphParent::Spawned(Waiting::init(
+            waiting.data_ptr.clone(),
+        )));
+        self
+    }
+
+    /// `node` runs after every task of `dependencies` is done
+    pub fn depends_on_group(
+        &mut self,
+        node: TaskNode,
+        dependencies: &TaskDependencies<F, FD, O>,
+    ) -> &mut TaskGraph<F, FD, O>
+    where
+        O: Sync,
+    {
+        let parents = self.parents(node);
+        for waiting in dependencies.waiting_list.iter() {
+            parents.push(GraphParent::Spawned(Waiting::init(
+                waiting.data_ptr.clone(),
+            )));
+        }
+        self
+    }
+
+    fn parents(&mut self, node: TaskNode) -> &mut Vec<GraphParent<O>> {
+        match self.nodes.get_mut(node.0) {
+            Some(node) => &mut node.parents,
+            None => panic!("{}", TaskGraphError::UnknownNode(node)),
+        }
+    }
+
+    /// node index in spawning order, parents before children
+    pub(crate) fn topological_order(&self) -> Result<Vec<usize>, TaskGraphError> {
+        // number of parents inside the graph, and children of each node
+        let mut in_degree = vec![0_usize; self.nodes.len()];
+        let mut children = vec![Vec::new(); self.nodes.len()];
+        for (index, node) in self.nodes.iter().enumerate() {
+            for parent in node.parents.iter() {
+                if let GraphParent::Node(parent) = parent {
+                    if *parent >= self.nodes.len() {
+                        return Err(TaskGraphError::UnknownNode(TaskNode(*parent)));
+                    }
+                    in_degree[index] += 1;
+                    children[*parent].push(index);
+                }
+            }
+        }
+
+        let mut order: Vec<usize> = (0..self.nodes.len())
+            .filter(|index| in_degree[*index] == 0)
+            .collect();
+        let mut cursor = 0;
+        while cursor < order.len() {
+            for child in children[order[cursor]].iter() {
+                in_degree[*child] -= 1;
+                if in_degree[*child] == 0 {
+                    order.push(*child);
+                }
+            }
+            cursor += 1;
+        }
+
+        // nodes left are part of a cycle
+        if order.len() < self.nodes.len() {
+            let index = (0..self.nodes.len())
+                .find(|index| in_degree[*index] > 0)
+                .unwrap();
+            return Err(TaskGraphError::Cycle(TaskNode(index)));
+        }
+
+        Ok(order)
+    }
+}
+
+impl<F, FD, O> Default for TaskGraph<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,125 @@
+use std::{
+    any::Any,
+    error::Error,
+    fmt::{self, Debug, Display},
+};
+
+use crate::{TaskNode, Waiting};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitingError {
+    /// the task did not finish before the timeout or deadline
+    Timeout,
+    /// the task panicked while executing
+    Panicked(TaskPanic),
+    /// the task was not executed, one of its dependencies panicked
+    DependencyFailed(TaskPanic),
+    /// the task was cancelled before running, or one of its dependencies was cancelled
+    Cancelled,
+}
+
+impl Display for WaitingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitingError::Timeout => write!(f, "waiting for task output timed out"),
+            WaitingError::Panicked(task_panic) => write!(f, "{}", task_panic),
+            WaitingError::DependencyFailed(task_panic) => {
+                write!(f, "dependency failed, {}", task_panic)
+            }
+            WaitingError::Cancelled => write!(f, "task was cancelled"),
+        }
+    }
+}
+
+impl Error for WaitingError {}
+
+/// panic caught from a task, the worker thread keeps running
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskPanic {
+    pub(crate) id: u64,
+    pub(crate) message: String,
+}
+
+impl TaskPanic {
+    pub(crate) fn from_payload(id: u64, payload: Box<dyn Any + Send>) -> TaskPanic {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Box<dyn Any>".to_string()
+        };
+
+        Self { id, message }
+    }
+
+    /// id of the task that panicked
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// panic message, if the payload is a string
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for TaskPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "task {} panicked: {}", self.id, self.message)
+    }
+}
+
+impl Error for TaskPanic {}
+
+pub enum TryCollectError<O>
+where
+    O: 'static,
+{
+    /// the task has not finished, the handle is given back
+    NotReady(Waiting<O>),
+    Failed(WaitingError),
+}
+
+impl<O> Debug for TryCollectError<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryCollectError::NotReady(_) => write!(f, "NotReady(..)"),
+            TryCollectError::Failed(error) => f.debug_tuple("Failed").field(error).finish(),
+        }
+    }
+}
+
+impl<O> Display for TryCollectError<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryCollectError::NotReady(_) => write!(f, "task has not finished"),
+            TryCollectError::Failed(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl<O> Error for TryCollectError<O> {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskGraphError {
+    /// the node is not part of this graph
+    UnknownNode(TaskNode),
+    /// the node depends on itself, directly or through other nodes
+    Cycle(TaskNode),
+}
+
+impl Display for TaskGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskGraphError::UnknownNode(node) => {
+                write!(f, "node {} is not part of the task graph", node.index())
+            }
+            TaskGraphError::Cycle(node) => {
+                write!(f, "node {} is part of a dependency cycle", node.index())
+            }
+        }
+    }
+}
+
+impl Error for TaskGraphError {}
@@ -0,0 +1,191 @@
+use std::{
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{
+        Arc, Mutex, Weak,
+        atomic::{AtomicU8, AtomicU64, Ordering},
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+use crate::{
+    ListCore, OutputTrait, TaskOnceTrait, TaskPanic, TaskWithDependenciesOnceTrait, WaitingError,
+    simboli_thread::list_core::wait::{CellState, ReturnCell},
+};
+
+pub(crate) type BoxFuture<O> = Pin<Box<dyn Future<Output = O> + Send>>;
+
+// poll state of a spawned future
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PollState {
+    // pending, waiting for a wake
+    Idle,
+    // a poll task is in a list or a local queue
+    Scheduled,
+    Running,
+    // woken while running, polled again right after
+    Notified,
+    Done,
+}
+
+// shared by the poll tasks and the wakers of a spawned future
+pub(crate) struct FutureCore<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    pub(crate) id: u64,
+    future: Mutex<Option<BoxFuture<O>>>,
+    state: AtomicU8,
+    pub(crate) return_ptr: Arc<ReturnCell>,
+    // counted once until the future is done, join waits for pending futures too
+    in_task: Arc<AtomicU64>,
+    // a future woken after the thread pool is dropped is dropped
+    list_core: Weak<ListCore<F, FD, O>>,
+}
+
+impl<F, FD, O> FutureCore<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    pub(crate) fn init(
+        id: u64,
+        future: BoxFuture<O>,
+        return_ptr: Arc<ReturnCell>,
+        list_core: &Arc<ListCore<F, FD, O>>,
+    ) -> FutureCore<F, FD, O> {
+        list_core.in_task.fetch_add(1, Ordering::SeqCst);
+        Self {
+            id,
+            future: Mutex::new(Some(future)),
+            state: AtomicU8::new(PollState::Scheduled as u8),
+            return_ptr,
+            in_task: list_core.in_task.clone(),
+            list_core: Arc::downgrade(list_core),
+        }
+    }
+
+    fn state(&self) -> PollState {
+        match self.state.load(Ordering::Acquire) {
+            0 => PollState::Idle,
+            1 => PollState::Scheduled,
+            2 => PollState::Running,
+            3 => PollState::Notified,
+            _ => PollState::Done,
+        }
+    }
+
+    fn transition(&self, from: PollState, to: PollState) -> bool {
+        self.state
+            .compare_exchange(from as u8, to as u8, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+
+    fn schedule(self: Arc<Self>) {
+        if let Some(list_core) = self.list_core.upgrade() {
+            list_core.push_to_swap_stack(list_core.create_poll_task(self));
+        }
+    }
+
+    fn finish(&self, output: Option<Result<O, WaitingError>>) {
+        self.state.store(PollState::Done as u8, Ordering::Release);
+        drop(self.future.lock().unwrap().take());
+        if let Some(output) = output {
+            self.return_ptr.store(output);
+        }
+
+        // release the count of the future, the poll task is still counted
+        self.in_task.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl<F, FD, O> Wake for FutureCore<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    fn wake(self: Arc<Self>) {
+        loop {
+            match self.state() {
+                PollState::Idle => {
+                    if self.transition(PollState::Idle, PollState::Scheduled) {
+                        self.schedule();
+                        return;
+                    }
+                }
+                PollState::Running => {
+                    if self.transition(PollState::Running, PollState::Notified) {
+                        return;
+                    }
+                }
+                // already scheduled, notified or done
+                _ => return,
+            }
+        }
+    }
+}
+
+/// poll of a future spawned with `spawn_future`, run by the workers like any other task
+pub struct FutureTask<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    pub(crate) future_core: Arc<FutureCore<F, FD, O>>,
+}
+
+impl<F, FD, O> FutureTask<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    // poll once, a pending future is pushed to the swap stack again when it is woken
+    pub(crate) fn poll(self) {
+        let future_core = self.future_core;
+
+        // first poll, pending to running. cancelled before the first poll, the output is
+        // already stored
+        if future_core.return_ptr.state() != CellState::Running
+            && !future_core.return_ptr.try_start()
+        {
+            future_core.finish(None);
+            return;
+        }
+
+        future_core
+            .state
+            .store(PollState::Running as u8, Ordering::Release);
+        let waker = Waker::from(future_core.clone());
+        let mut context = Context::from_waker(&waker);
+
+        let poll = {
+            let mut future = future_core.future.lock().unwrap();
+            let future = future.as_mut().expect("future polled after completion");
+            // catch panic, keep the worker alive
+            panic::catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(&mut context)))
+        };
+
+        match poll {
+            Ok(Poll::Ready(output)) => future_core.finish(Some(Ok(output))),
+            Err(payload) => future_core.finish(Some(Err(WaitingError::Panicked(
+                TaskPanic::from_payload(future_core.id, payload),
+            )))),
+            Ok(Poll::Pending) => {
+                if !future_core.transition(PollState::Running, PollState::Idle) {
+                    // woken while running
+                    future_core
+                        .state
+                        .store(PollState::Scheduled as u8, Ordering::Release);
+                    future_core.schedule();
+                }
+            }
+        }
+    }
+}
@@ -4,5 +4,30 @@ pub use waiting::*;
 mod waiting_task;
 pub use waiting_task::*;
 
+mod closure_task;
+pub use closure_task::*;
+
+mod return_cell;
+pub use return_cell::AnyOutput;
+pub(crate) use return_cell::{CellState, ReturnCell};
+
+mod continuation;
+pub use continuation::ContinuationTask;
+pub(crate) use continuation::{Continuation, Spawner};
+
+mod future_task;
+pub(crate) use future_task::FutureCore;
+pub use future_task::FutureTask;
+
+mod cancellation;
+pub use cancellation::*;
+
+mod periodic;
+pub(crate) use periodic::PeriodicCore;
+pub use periodic::{PeriodicHandle, PeriodicMode};
+
+mod error;
+pub use error::*;
+
 mod dependencies_task;
 pub use dependencies_task::*;
@@ -0,0 +1,115 @@
+use std::{
+    sync::{
+        Arc, Mutex, Weak,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::simboli_thread::list_core::wait::ReturnCell;
+
+/// when the next run of a periodic task is due
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeriodicMode {
+    /// every `interval` from the first run, a run is never started before the previous one is done.
+    /// runs that are already late are skipped when `skip_missed` is set, otherwise they run back to back
+    FixedRate { skip_missed: bool },
+    /// `interval` after the previous run is done
+    FixedDelay,
+}
+
+impl Default for PeriodicMode {
+    fn default() -> Self {
+        PeriodicMode::FixedRate { skip_missed: true }
+    }
+}
+
+impl PeriodicMode {
+    // `deadline` of the run that is done, at `now`
+    pub(crate) fn next_deadline(
+        &self,
+        interval: Duration,
+        deadline: Instant,
+        now: Instant,
+    ) -> Instant {
+        match self {
+            PeriodicMode::FixedDelay => now + interval,
+            PeriodicMode::FixedRate { skip_missed } => {
+                let next = deadline + interval;
+                let behind = now.saturating_duration_since(next);
+                if *skip_missed && !behind.is_zero() {
+                    // first deadline after now
+                    let missed = behind.as_nanos() / interval.as_nanos() + 1;
+                    next + Duration::from_nanos((interval.as_nanos() * missed) as u64)
+                } else {
+                    next
+                }
+            }
+        }
+    }
+}
+
+pub(crate) struct PeriodicCore {
+    stopped: AtomicBool,
+    runs: AtomicU64,
+    // next run, waiting for its deadline
+    pending: Mutex<Option<Weak<ReturnCell>>>,
+}
+
+impl PeriodicCore {
+    pub(crate) fn init() -> PeriodicCore {
+        Self {
+            stopped: AtomicBool::new(false),
+            runs: AtomicU64::new(0),
+            pending: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn stop(&self) {
+        self.stopped.store(true, Ordering::Release);
+
+        let pending = self.pending.lock().unwrap().take();
+        if let Some(return_ptr) = pending.as_ref().and_then(Weak::upgrade) {
+            return_ptr.cancel();
+        }
+    }
+
+    // the next run, cancelled if the task was stopped meanwhile
+    pub(crate) fn set_pending(&self, return_ptr: &Arc<ReturnCell>) {
+        *self.pending.lock().unwrap() = Some(Arc::downgrade(return_ptr));
+        if self.is_stopped() {
+            return_ptr.cancel();
+        }
+    }
+
+    pub(crate) fn add_run(&self) {
+        self.runs.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// handle of a task spawned with `spawn_periodic`, dropping it does not stop the task.
+/// `SimboliThread::join` stops every periodic task
+#[derive(Clone)]
+pub struct PeriodicHandle {
+    pub(crate) periodic_core: Arc<PeriodicCore>,
+}
+
+impl PeriodicHandle {
+    /// no run is started after `stop`, a running one is finished
+    pub fn stop(&self) {
+        self.periodic_core.stop();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.periodic_core.is_stopped()
+    }
+
+    /// number of finished runs
+    pub fn runs(&self) -> u64 {
+        self.periodic_core.runs.load(Ordering::Acquire)
+    }
+}
@@ -0,0 +1,456 @@
+use std::{
+    any::{Any, TypeId},
+    ptr::{self, null_mut},
+    sync::{
+        Arc, Condvar, Mutex, OnceLock, Weak,
+        atomic::{AtomicBool, AtomicPtr, AtomicU8, AtomicUsize, Ordering, fence},
+    },
+    task::Waker,
+    time::Instant,
+};
+
+use crate::{
+    WaitingError,
+    simboli_thread::list_core::wait::{Continuation, Spawner},
+};
+
+/// type erased task output
+pub type AnyOutput = Box<dyn Any + Send>;
+
+// called once the output is stored
+pub(crate) type Dependent = Box<dyn FnOnce() + Send>;
+
+// lock-free stack of dependents
+struct DependentNode {
+    dependent: Dependent,
+    next: *mut DependentNode,
+}
+
+// the output is stored, dependents registered after this run directly
+fn closed() -> *mut DependentNode {
+    ptr::without_provenance_mut(1)
+}
+
+// output of a task in its own type, a typed thread pool stores it without boxing it again
+#[repr(C)]
+struct Slot<O> {
+    header: SlotHeader,
+    // None when the task failed
+    output: Option<O>,
+}
+
+// first field of every slot, read without knowing the output type
+struct SlotHeader {
+    type_id: TypeId,
+    error: Option<WaitingError>,
+    drop: unsafe fn(*mut SlotHeader),
+    into_any: unsafe fn(*mut SlotHeader) -> Option<AnyOutput>,
+}
+
+unsafe fn drop_slot<O>(slot: *mut SlotHeader) {
+    drop(unsafe { Box::from_raw(slot as *mut Slot<O>) });
+}
+
+// frees the slot, an output that is already an AnyOutput is not boxed again
+unsafe fn slot_into_any<O>(slot: *mut SlotHeader) -> Option<AnyOutput>
+where
+    O: Send + 'static,
+{
+    let slot = unsafe { Box::from_raw(slot as *mut Slot<O>) };
+    slot.output.map(|output| {
+        let output: AnyOutput = Box::new(output);
+        match output.downcast::<AnyOutput>() {
+            Ok(output) => *output,
+            Err(output) => output,
+        }
+    })
+}
+
+// spawner of a detached return cell, never created
+enum Detached {}
+
+impl Spawner for Detached {
+    fn spawn_continuation(
+        self: Arc<Self>,
+        _parent: Arc<ReturnCell>,
+        _continuation: Continuation,
+    ) -> Arc<ReturnCell> {
+        match *self {}
+    }
+}
+
+// run state of the task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CellState {
+    Pending,
+    Running,
+    Done,
+    Cancelled,
+}
+
+// shared between Waiting<O> and WaitingTask<F, FD, O> through an Arc,
+// the output is freed when the last side lets go.
+// the output is type erased, Waiting<O> gives the typed view
+pub(crate) struct ReturnCell {
+    // task
+    id: u64,
+    label: OnceLock<String>,
+    state: AtomicU8,
+    // output
+    data_ptr: AtomicPtr<SlotHeader>,
+    // Waiting handles, tasks waiting on this output hold one too.
+    // the output is only taken by the last handle, the others may be reading it
+    handles: AtomicUsize,
+    // notification, only touched when someone is blocking
+    waiters: AtomicUsize,
+    lock: Mutex<()>,
+    condvar: Condvar,
+    // waker of the future awaiting the output
+    waker: Mutex<Option<Waker>>,
+    has_waker: AtomicBool,
+    // tasks waiting on this output
+    dependents: AtomicPtr<DependentNode>,
+    // list core of the task, continuations are spawned there
+    spawner: Weak<dyn Spawner>,
+}
+
+impl ReturnCell {
+    pub(crate) fn init(id: u64, spawner: Weak<dyn Spawner>) -> ReturnCell {
+        Self {
+            id,
+            label: OnceLock::new(),
+            state: AtomicU8::new(CellState::Pending as u8),
+            data_ptr: AtomicPtr::new(null_mut()),
+            handles: AtomicUsize::new(0),
+            waiters: AtomicUsize::new(0),
+            lock: Mutex::new(()),
+            condvar: Condvar::new(),
+            waker: Mutex::new(None),
+            has_waker: AtomicBool::new(false),
+            dependents: AtomicPtr::new(null_mut()),
+            spawner,
+        }
+    }
+
+    /// outside of any thread pool, `spawner` is always `None`
+    pub(crate) fn detached(id: u64) -> ReturnCell {
+        Self::init(id, Weak::<Detached>::new())
+    }
+
+    /// the first output is kept, a later one is dropped
+    pub(crate) fn store<O>(&self, output: Result<O, WaitingError>)
+    where
+        O: Send + 'static,
+    {
+        let (output, error) = match output {
+            Ok(output) => (Some(output), None),
+            Err(error) => (None, Some(error)),
+        };
+        let slot = Box::into_raw(Box::new(Slot {
+            header: SlotHeader {
+                type_id: TypeId::of::<O>(),
+                error,
+                drop: drop_slot::<O>,
+                into_any: slot_into_any::<O>,
+            },
+            output,
+        })) as *mut SlotHeader;
+        let status =
+            self.data_ptr
+                .compare_exchange(null_mut(), slot, Ordering::AcqRel, Ordering::Acquire);
+        if status.is_err() {
+            // the output may be borrowed already, keep it
+            unsafe { drop_slot::<O>(slot) };
+            return;
+        }
+        // a cancelled task keeps its state
+        let _ = self
+            .state
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |state| {
+                (state != CellState::Cancelled as u8).then_some(CellState::Done as u8)
+            });
+
+        self.notify();
+
+        // run dependents, later ones run directly
+        let mut node = self.dependents.swap(closed(), Ordering::AcqRel);
+        while !node.is_null() && node != closed() {
+            let DependentNode { dependent, next } = *unsafe { Box::from_raw(node) };
+            dependent();
+            node = next;
+        }
+    }
+
+    /// run `dependent` once the output is stored, directly if it is already stored
+    pub(crate) fn on_ready(&self, dependent: Dependent) {
+        let node = Box::into_raw(Box::new(DependentNode {
+            dependent,
+            next: null_mut(),
+        }));
+
+        let mut head = self.dependents.load(Ordering::Acquire);
+        loop {
+            if head == closed() {
+                let DependentNode { dependent, .. } = *unsafe { Box::from_raw(node) };
+                dependent();
+                return;
+            }
+
+            unsafe { (*node).next = head };
+            match self.dependents.compare_exchange_weak(
+                head,
+                node,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// new Waiting handle
+    pub(crate) fn acquire(&self) {
+        self.handles.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Waiting handle dropped, the last one left may take the output now
+    pub(crate) fn release(&self) {
+        if self.handles.fetch_sub(1, Ordering::SeqCst) == 2 {
+            self.notify();
+        }
+    }
+
+    /// `None` once the thread pool is dropped
+    pub(crate) fn spawner(&self) -> Option<Arc<dyn Spawner>> {
+        self.spawner.upgrade()
+    }
+
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// the first label is kept
+    pub(crate) fn set_label(&self, label: String) {
+        let _ = self.label.set(label);
+    }
+
+    pub(crate) fn label(&self) -> Option<&str> {
+        self.label.get().map(String::as_str)
+    }
+
+    /// pending to running, false if the task was cancelled
+    pub(crate) fn try_start(&self) -> bool {
+        self.state
+            .compare_exchange(
+                CellState::Pending as u8,
+                CellState::Running as u8,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .is_ok()
+    }
+
+    /// pending to cancelled, the output becomes `WaitingError::Cancelled`.
+    /// false if the task is already running or done
+    pub(crate) fn cancel(&self) -> bool {
+        let status = self.state.compare_exchange(
+            CellState::Pending as u8,
+            CellState::Cancelled as u8,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+        if status.is_err() {
+            return false;
+        }
+
+        self.store::<()>(Err(WaitingError::Cancelled));
+        true
+    }
+
+    pub(crate) fn state(&self) -> CellState {
+        match self.state.load(Ordering::Acquire) {
+            0 => CellState::Pending,
+            1 => CellState::Running,
+            2 => CellState::Done,
+            _ => CellState::Cancelled,
+        }
+    }
+
+    fn load(&self) -> *mut SlotHeader {
+        self.data_ptr.load(Ordering::Acquire)
+    }
+
+    /// failure of a finished task
+    pub(crate) fn error(&self) -> Option<&WaitingError> {
+        let slot = self.load();
+        if slot.is_null() {
+            None
+        } else {
+            unsafe { (*slot).error.as_ref() }
+        }
+    }
+
+    /// `None` if the output is not stored, `Ok(None)` if the output is not an `O`
+    pub(crate) fn output_ref<O>(&self) -> Option<Result<Option<&O>, WaitingError>>
+    where
+        O: 'static,
+    {
+        let slot = self.load();
+        if slot.is_null() {
+            return None;
+        }
+
+        let header = unsafe { &*slot };
+        if let Some(error) = &header.error {
+            return Some(Err(error.clone()));
+        }
+
+        let output = if header.type_id == TypeId::of::<O>() {
+            unsafe { (*(slot as *const Slot<O>)).output.as_ref() }
+        } else if header.type_id == TypeId::of::<AnyOutput>() {
+            // type erased output, look into the box
+            unsafe { (*(slot as *const Slot<AnyOutput>)).output.as_ref() }
+                .and_then(|output| output.downcast_ref::<O>())
+        } else {
+            None
+        };
+        Some(Ok(output))
+    }
+
+    /// take the output out, `None` if it is not stored, `Ok(None)` if it is not an `O`
+    pub(crate) fn take<O>(&self) -> Option<Result<Option<O>, WaitingError>>
+    where
+        O: 'static,
+    {
+        let slot = self.data_ptr.swap(null_mut(), Ordering::AcqRel);
+        if slot.is_null() {
+            return None;
+        }
+
+        let header = unsafe { &mut *slot };
+        if let Some(error) = header.error.take() {
+            unsafe { (header.drop)(slot) };
+            return Some(Err(error));
+        }
+
+        if header.type_id == TypeId::of::<O>() {
+            let slot = unsafe { Box::from_raw(slot as *mut Slot<O>) };
+            return Some(Ok(slot.output));
+        }
+
+        // O is AnyOutput or the slot holds an AnyOutput
+        let output =
+            unsafe { (header.into_any)(slot) }.and_then(|output| match output.downcast::<O>() {
+                Ok(output) => Some(*output),
+                Err(output) => (Box::new(output) as Box<dyn Any>)
+                    .downcast::<O>()
+                    .ok()
+                    .map(|output| *output),
+            });
+        Some(Ok(output))
+    }
+
+    /// the output is stored and no other handle can read it
+    pub(crate) fn is_collectable(&self) -> bool {
+        self.is_stored() && self.handles.load(Ordering::SeqCst) <= 1
+    }
+
+    /// sleep until the output is stored
+    pub(crate) fn wait(&self) {
+        self.wait_until(None, Self::is_stored);
+    }
+
+    /// sleep until the output is stored or the deadline has passed, return false on timeout
+    pub(crate) fn wait_deadline(&self, deadline: Instant) -> bool {
+        self.wait_until(Some(deadline), Self::is_stored)
+    }
+
+    /// sleep until the output can be taken, see `is_collectable`
+    pub(crate) fn wait_collectable(&self) {
+        self.wait_until(None, Self::is_collectable);
+    }
+
+    /// wake `waker` once the output can be taken, return true if it can be taken already
+    pub(crate) fn register_waker(&self, waker: &Waker) -> bool {
+        if self.is_collectable() {
+            return true;
+        }
+
+        {
+            let mut slot = self.waker.lock().unwrap();
+            if !slot.as_ref().is_some_and(|slot| slot.will_wake(waker)) {
+                *slot = Some(waker.clone());
+            }
+        }
+        self.has_waker.store(true, Ordering::SeqCst);
+        fence(Ordering::SeqCst);
+
+        // stored or released meanwhile, the waker may have been missed
+        self.is_collectable()
+    }
+
+    pub(crate) fn is_stored(&self) -> bool {
+        !self.load().is_null()
+    }
+
+    // wake blocking waiters and the waker
+    fn notify(&self) {
+        fence(Ordering::SeqCst);
+        if self.waiters.load(Ordering::SeqCst) > 0 {
+            let _guard = self.lock.lock().unwrap();
+            self.condvar.notify_all();
+        }
+        if self.has_waker.load(Ordering::SeqCst) {
+            let waker = self.waker.lock().unwrap().take();
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+
+    fn wait_until(&self, deadline: Option<Instant>, ready: fn(&ReturnCell) -> bool) -> bool {
+        if ready(self) {
+            return true;
+        }
+
+        let mut guard = self.lock.lock().unwrap();
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        fence(Ordering::SeqCst);
+
+        let status = loop {
+            if ready(self) {
+                break true;
+            }
+
+            if let Some(deadline) = deadline {
+                let now = Instant::now();
+                if now >= deadline {
+                    break false;
+                }
+                guard = self.condvar.wait_timeout(guard, deadline - now).unwrap().0;
+            } else {
+                guard = self.condvar.wait(guard).unwrap();
+            }
+        };
+
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+        status
+    }
+}
+
+impl Drop for ReturnCell {
+    fn drop(&mut self) {
+        let slot = self.data_ptr.swap(null_mut(), Ordering::AcqRel);
+        if !slot.is_null() {
+            unsafe { ((*slot).drop)(slot) };
+        }
+
+        // never stored, dependents are dropped without running
+        let mut node = self.dependents.swap(null_mut(), Ordering::AcqRel);
+        while !node.is_null() && node != closed() {
+            let node_box = unsafe { Box::from_raw(node) };
+            node = node_box.next;
+        }
+    }
+}
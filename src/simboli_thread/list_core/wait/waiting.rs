@@ -1,40 +1,274 @@
 use std::{
-    hint::spin_loop,
-    ops::{Deref, DerefMut},
-    sync::{
-        Arc,
-        atomic::{AtomicPtr, Ordering},
-    },
+    any::type_name,
+    future::{Future, IntoFuture},
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    pin::Pin,
+    ptr,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
+use crate::{
+    ContinuationTask, TryCollectError, WaitingError,
+    simboli_thread::list_core::wait::{Continuation, ReturnCell},
+};
+
+#[repr(transparent)]
 pub struct Waiting<O>
 where
     O: 'static,
 {
-    pub(crate) data_ptr: &'static AtomicPtr<O>,
+    pub(crate) data_ptr: Arc<ReturnCell>,
+    pub(crate) _marker: PhantomData<O>,
 }
 
 impl<O> Waiting<O> {
-    pub fn block(&self) -> Option<&O> {
-        while self.data_ptr.load(Ordering::Acquire).is_null() {
-            spin_loop();
+    pub(crate) fn init(data_ptr: Arc<ReturnCell>) -> Waiting<O> {
+        data_ptr.acquire();
+        Self {
+            data_ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    /// id of the task, same as in `GraphSnapshot`
+    pub fn id(&self) -> u64 {
+        self.data_ptr.id()
+    }
+
+    /// label of the task in `GraphSnapshot`, the first label is kept
+    pub fn set_label(&self, label: impl Into<String>) {
+        self.data_ptr.set_label(label.into());
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.data_ptr.label()
+    }
+
+    /// cancel the task if it has not started, the output becomes `WaitingError::Cancelled`
+    /// and tasks waiting on it are cancelled too. false if the task is already running or done
+    pub fn cancel(&self) -> bool {
+        self.data_ptr.cancel()
+    }
+
+    /// block the current thread until the task output is stored
+    pub fn block(&self) -> Result<&O, WaitingError> {
+        self.data_ptr.wait();
+
+        self.stored().unwrap()
+    }
+
+    /// block the current thread until the task output is stored, at most for `timeout`
+    pub fn block_timeout(&self, timeout: Duration) -> Result<&O, WaitingError> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.block_deadline(deadline),
+            // too far in the future to be represented, same as block
+            None => self.block(),
+        }
+    }
+
+    /// block the current thread until the task output is stored, at most until `deadline`
+    pub fn block_deadline(&self, deadline: Instant) -> Result<&O, WaitingError> {
+        if !self.data_ptr.wait_deadline(deadline) {
+            return Err(WaitingError::Timeout);
         }
 
-        unsafe { Some(&*self.data_ptr.load(Ordering::Acquire)) }
+        self.stored().unwrap()
     }
 
+    /// task output without blocking, `None` if the task has not finished, failed or did not
+    /// return an `O`
     pub fn get(&self) -> Option<&O> {
-        unsafe { Some(&*self.data_ptr.load(Ordering::Acquire)) }
+        match self.data_ptr.output_ref() {
+            Some(Ok(output)) => output,
+            _ => None,
+        }
+    }
+
+    /// has the task finished? true as well when the task failed
+    pub fn is_ready(&self) -> bool {
+        self.data_ptr.is_stored()
+    }
+
+    /// block the current thread until the task output is stored and take it,
+    /// tasks waiting on this task read the output in place and are waited for too
+    pub fn collect(self) -> Result<O, WaitingError> {
+        self.data_ptr.wait_collectable();
+
+        self.take()
+    }
+
+    /// take the task output without blocking, the handle is given back if the task has not
+    /// finished or tasks waiting on it are still reading the output
+    pub fn try_collect(self) -> Result<O, TryCollectError<O>> {
+        if !self.data_ptr.is_collectable() {
+            return Err(TryCollectError::NotReady(self));
+        }
+
+        self.take().map_err(TryCollectError::Failed)
+    }
+
+    /// typed view of a type erased output (`AnyOutput`),
+    /// reading the output panics if the task did not return a `T`, `get` gives `None`
+    pub fn downcast<T>(self) -> Waiting<T> {
+        // the handle moves over, the count stays the same
+        let waiting = ManuallyDrop::new(self);
+        Waiting {
+            data_ptr: unsafe { ptr::read(&waiting.data_ptr) },
+            _marker: PhantomData,
+        }
+    }
+
+    /// typed view of a type erased output (`AnyOutput`),
+    /// reading the output panics if the task did not return a `T`, `get` gives `None`
+    pub fn downcast_ref<T>(&self) -> &Waiting<T> {
+        // same layout, Waiting is transparent over the return cell
+        unsafe { &*(self as *const Waiting<O> as *const Waiting<T>) }
+    }
+
+    /// `downcast_ref` of a finished task, `None` if the task has not finished or did not return
+    /// a `T`. a failed task gives its failure
+    pub fn try_downcast_ref<T>(&self) -> Option<&Waiting<T>> {
+        match self.data_ptr.output_ref::<T>()? {
+            Ok(None) => None,
+            _ => Some(self.downcast_ref()),
+        }
     }
 
-    pub fn collect(self) -> O {
-        unsafe {
-            while self.data_ptr.load(Ordering::Acquire).is_null() {
-                spin_loop();
+    /// spawn `f` on the thread pool once the task output is stored, `f` gets the output or the
+    /// failure of the task. `collect` of this `Waiting` waits until the continuation is done.
+    /// once the thread pool is dropped, `f` runs right away on the calling thread
+    pub fn then<U, T>(&self, f: T) -> Waiting<U>
+    where
+        O: Send + Sync,
+        U: Send + 'static,
+        T: FnOnce(Result<&O, WaitingError>) -> U + Send + 'static,
+    {
+        self.continue_with(|output| Ok(f(output)))
+    }
+
+    /// spawn `f` on the thread pool once the task is done, a failure of the task becomes the
+    /// failure of the continuation (`WaitingError::DependencyFailed` or `WaitingError::Cancelled`)
+    pub fn map<U, T>(&self, f: T) -> Waiting<U>
+    where
+        O: Send + Sync,
+        U: Send + 'static,
+        T: FnOnce(&O) -> U + Send + 'static,
+    {
+        self.continue_with(|output| output.map_err(dependency_error).map(f))
+    }
+
+    /// `map` where `f` can fail, an `Err` of `f` is the output of the continuation
+    pub fn and_then<U, T>(&self, f: T) -> Waiting<U>
+    where
+        O: Send + Sync,
+        U: Send + 'static,
+        T: FnOnce(&O) -> Result<U, WaitingError> + Send + 'static,
+    {
+        self.continue_with(|output| output.map_err(dependency_error).and_then(f))
+    }
+
+    fn continue_with<U, T>(&self, f: T) -> Waiting<U>
+    where
+        O: Send + Sync,
+        U: Send + 'static,
+        T: FnOnce(Result<&O, WaitingError>) -> Result<U, WaitingError> + Send + 'static,
+    {
+        let parent = Waiting::<O>::init(self.data_ptr.clone());
+        let continuation: Continuation = Box::new(move |return_ptr: &ReturnCell| {
+            // a task dropped with its thread pool never stores an output
+            let output = parent.stored().unwrap_or(Err(WaitingError::Cancelled));
+            return_ptr.store(f(output));
+        });
+
+        let return_ptr = match self.data_ptr.spawner() {
+            Some(spawner) => spawner.spawn_continuation(self.data_ptr.clone(), continuation),
+            // thread pool is dropped, nothing is left to wait for, run it here
+            None => {
+                let return_ptr = Arc::new(ReturnCell::detached(self.data_ptr.id()));
+                ContinuationTask::init(continuation).run(return_ptr.id(), &return_ptr);
+                return_ptr
             }
+        };
+
+        Waiting::init(return_ptr)
+    }
+
+    /// failure of a finished task
+    pub(crate) fn error(&self) -> Option<&WaitingError> {
+        self.data_ptr.error()
+    }
+
+    fn stored(&self) -> Option<Result<&O, WaitingError>> {
+        self.data_ptr
+            .output_ref()
+            .map(|output| output.map(|output| output.unwrap_or_else(|| mismatch::<O>())))
+    }
+
+    fn take(&self) -> Result<O, WaitingError> {
+        self.data_ptr
+            .take()
+            .unwrap()
+            .map(|output| output.unwrap_or_else(|| mismatch::<O>()))
+    }
+}
+
+impl<O> Drop for Waiting<O> {
+    fn drop(&mut self) {
+        self.data_ptr.release();
+    }
+}
 
-            let data_box = Box::from_raw(self.data_ptr.load(Ordering::Acquire));
-            *data_box
+/// `.await` the task output without blocking the thread, the worker wakes the task once the
+/// output is stored
+impl<O> IntoFuture for Waiting<O> {
+    type Output = Result<O, WaitingError>;
+    type IntoFuture = WaitingFuture<O>;
+
+    fn into_future(self) -> WaitingFuture<O> {
+        WaitingFuture {
+            waiting: Some(self),
+        }
+    }
+}
+
+/// future of a `Waiting`, from `Waiting::into_future`
+pub struct WaitingFuture<O>
+where
+    O: 'static,
+{
+    waiting: Option<Waiting<O>>,
+}
+
+// the output is never pinned
+impl<O> Unpin for WaitingFuture<O> {}
+
+impl<O> Future for WaitingFuture<O> {
+    type Output = Result<O, WaitingError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let waiting = self
+            .waiting
+            .as_ref()
+            .expect("waiting future polled after completion");
+        if !waiting.data_ptr.register_waker(cx.waker()) {
+            return Poll::Pending;
         }
+
+        Poll::Ready(self.waiting.take().unwrap().take())
     }
 }
+
+// failure of a parent, as seen by the task waiting on it
+fn dependency_error(error: WaitingError) -> WaitingError {
+    match error {
+        WaitingError::Panicked(task_panic) => WaitingError::DependencyFailed(task_panic),
+        error => error,
+    }
+}
+
+fn mismatch<O>() -> ! {
+    panic!("task output is not a `{}`", type_name::<O>())
+}
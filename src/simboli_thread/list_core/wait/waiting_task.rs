@@ -1,35 +1,67 @@
-use std::sync::atomic::AtomicPtr;
+use std::sync::{Arc, atomic::AtomicPtr};
 
 use crate::{
-    TaskDependencies, Waiting,
-    simboli_thread::list_core::wait::dependencies_task::TaskDependenciesCore,
+    ContinuationTask, FutureTask, Priority, Waiting,
+    simboli_thread::list_core::wait::{ReturnCell, dependencies_task::TaskDependenciesCore},
 };
 
 pub struct WaitingTask<F, FD, O>
 where
-    F: TaskTrait<O> + Send + 'static,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
     pub(crate) id: u64,
     pub(crate) task: ExecTask<F, FD, O>,
     pub(crate) next: AtomicPtr<WaitingTask<F, FD, O>>,
-    pub(crate) waiting_return_ptr: &'static AtomicPtr<O>,
+    pub(crate) waiting_return_ptr: Arc<ReturnCell>,
     // dependencies
-    pub(crate) task_dependencies_core_ptr: &'static TaskDependenciesCore<F, FD, O>, // will be shared. to Waiting<O> and WaitingTask<F, O>
-    pub(crate) task_dependencies_ptr: &'static Vec<Waiting<O>>,
+    pub(crate) task_dependencies_core_ptr: Option<Arc<TaskDependenciesCore<F, FD, O>>>, // will be shared. to Waiting<O> and WaitingTask<F, O>
+    pub(crate) task_dependencies_ptr: Option<Arc<Vec<Waiting<O>>>>,
+    pub(crate) priority: Priority,
 }
 
+impl<F, FD, O> WaitingTask<F, FD, O>
+where
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
+    O: 'static + OutputTrait + Send,
+{
+    // without dependencies, set the fields after for a task that has them
+    pub(crate) fn init(
+        id: u64,
+        task: ExecTask<F, FD, O>,
+        waiting_return_ptr: Arc<ReturnCell>,
+        priority: Priority,
+    ) -> WaitingTask<F, FD, O> {
+        Self {
+            id,
+            task,
+            next: AtomicPtr::new(std::ptr::null_mut()),
+            waiting_return_ptr,
+            task_dependencies_core_ptr: None,
+            task_dependencies_ptr: None,
+            priority,
+        }
+    }
+}
+
+/// implemented for every type, any type can be a task output.
+/// since 0.0.3, own impls conflict with this one and are removed
 pub trait OutputTrait {}
 
+impl<O> OutputTrait for O {}
+
 pub enum ExecTask<F, FD, O>
 where
-    F: TaskTrait<O> + Send + 'static,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + Send + 'static,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
     Task(F),
     TaskWithDependencies(FD),
+    Future(FutureTask<F, FD, O>),
+    Continuation(ContinuationTask),
     _Output(O),
 }
 
@@ -48,9 +80,64 @@ pub trait TaskWithDependenciesTrait<O>
 where
     O: OutputTrait + 'static + Send,
 {
-    fn exec(&self, dependencies: &'static Vec<Waiting<O>>) -> O;
+    fn exec(&self, dependencies: &[Waiting<O>]) -> O;
 
     fn is_with_dependencies(&self) -> bool {
         true
     }
 }
+
+/// consuming version of `TaskTrait`, the task is moved into `exec_once` so it can hand
+/// ownership of its data to the output without cloning.
+/// implemented for every `TaskTrait`
+pub trait TaskOnceTrait<O>
+where
+    O: OutputTrait,
+{
+    fn exec_once(self) -> O;
+
+    fn is_with_dependencies(&self) -> bool {
+        false
+    }
+}
+
+impl<T, O> TaskOnceTrait<O> for T
+where
+    T: TaskTrait<O>,
+    O: OutputTrait,
+{
+    fn exec_once(self) -> O {
+        self.exec()
+    }
+
+    fn is_with_dependencies(&self) -> bool {
+        TaskTrait::is_with_dependencies(self)
+    }
+}
+
+/// consuming version of `TaskWithDependenciesTrait`.
+/// implemented for every `TaskWithDependenciesTrait`
+pub trait TaskWithDependenciesOnceTrait<O>
+where
+    O: OutputTrait + 'static + Send,
+{
+    fn exec_once(self, dependencies: &[Waiting<O>]) -> O;
+
+    fn is_with_dependencies(&self) -> bool {
+        true
+    }
+}
+
+impl<T, O> TaskWithDependenciesOnceTrait<O> for T
+where
+    T: TaskWithDependenciesTrait<O>,
+    O: OutputTrait + 'static + Send,
+{
+    fn exec_once(self, dependencies: &[Waiting<O>]) -> O {
+        self.exec(dependencies)
+    }
+
+    fn is_with_dependencies(&self) -> bool {
+        TaskWithDependenciesTrait::is_with_dependencies(self)
+    }
+}
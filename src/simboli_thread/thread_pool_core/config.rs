@@ -0,0 +1,79 @@
+use std::{ops::Range, thread};
+
+use crate::IdleStrategy;
+
+// how the threads are split into harvesting groups
+#[derive(Debug, Clone)]
+pub(crate) enum GroupTopology {
+    // every group has the same size, the last group takes the threads left and can be smaller
+    Size(usize),
+    // one group for each cluster, the size of each cluster
+    Clusters(Vec<usize>),
+}
+
+// runtime values of the thread pool, filled by SimboliThreadBuilder
+#[derive(Debug, Clone)]
+pub(crate) struct PoolConfig {
+    pub(crate) threads: usize,
+    pub(crate) queue_capacity: usize,
+    pub(crate) groups: GroupTopology,
+    pub(crate) harvest_interval: usize,
+    pub(crate) thread_name: Option<String>,
+    pub(crate) idle_strategy: IdleStrategy,
+    pub(crate) graph_tracing: bool,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            queue_capacity: 64,
+            groups: GroupTopology::Size(2),
+            harvest_interval: 32,
+            thread_name: None,
+            idle_strategy: IdleStrategy::default(),
+            graph_tracing: false,
+        }
+    }
+}
+
+impl PoolConfig {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if let GroupTopology::Clusters(clusters) = &self.groups {
+            if clusters.contains(&0) {
+                return Err("cluster size must be greater than zero".to_string());
+            }
+
+            let total: usize = clusters.iter().sum();
+            if total != self.threads {
+                return Err(format!(
+                    "clusters have {} threads in total, the thread pool has {} threads",
+                    total, self.threads
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    // thread ids of each group, in order
+    pub(crate) fn group_ranges(&self) -> Vec<Range<usize>> {
+        match &self.groups {
+            GroupTopology::Size(size) => (0..self.threads)
+                .step_by(*size)
+                .map(|start| start..(start + size).min(self.threads))
+                .collect(),
+            GroupTopology::Clusters(clusters) => {
+                let mut start = 0;
+                clusters
+                    .iter()
+                    .map(|size| {
+                        let group = start..start + size;
+                        start += size;
+                        group
+                    })
+                    .collect()
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering, fence},
+    },
+    thread::{self, Thread},
+    time::Duration,
+};
+
+/// how an idle worker waits for new task.
+/// the worker spins `spin_rounds` times, then yields `yield_rounds` times, then parks until
+/// new task is spawned or `park_timeout` has passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleStrategy {
+    pub spin_rounds: u32,
+    pub yield_rounds: u32,
+    pub park_timeout: Duration,
+}
+
+impl IdleStrategy {
+    pub fn new(spin_rounds: u32, yield_rounds: u32, park_timeout: Duration) -> IdleStrategy {
+        Self {
+            spin_rounds,
+            yield_rounds,
+            park_timeout,
+        }
+    }
+
+    /// never park, the worker keeps spinning while waiting for new task
+    pub fn busy_spin() -> IdleStrategy {
+        Self {
+            spin_rounds: u32::MAX,
+            yield_rounds: 0,
+            park_timeout: Duration::ZERO,
+        }
+    }
+}
+
+impl Default for IdleStrategy {
+    fn default() -> Self {
+        Self {
+            spin_rounds: 128,
+            yield_rounds: 32,
+            park_timeout: Duration::from_millis(50),
+        }
+    }
+}
+
+// parked workers, shared through list core so spawning can wake them up
+pub(crate) struct IdleHandler {
+    sleeping: AtomicUsize,
+    sleepers: Mutex<Vec<Thread>>,
+}
+
+impl IdleHandler {
+    pub(crate) fn init() -> IdleHandler {
+        Self {
+            sleeping: AtomicUsize::new(0),
+            sleepers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// park current thread, `has_task` is checked after registering as sleeper so a task spawned
+    /// in between is never missed
+    pub(crate) fn park<T>(&self, timeout: Duration, has_task: T)
+    where
+        T: Fn() -> bool,
+    {
+        let current = thread::current();
+        {
+            let mut sleepers = self.sleepers.lock().unwrap();
+            sleepers.push(current.clone());
+            self.sleeping.fetch_add(1, Ordering::SeqCst);
+        }
+        fence(Ordering::SeqCst);
+
+        if !has_task() {
+            thread::park_timeout(timeout);
+        }
+
+        // not woken by wake_one, remove by self
+        let mut sleepers = self.sleepers.lock().unwrap();
+        if let Some(index) = sleepers.iter().position(|t| t.id() == current.id()) {
+            sleepers.swap_remove(index);
+            self.sleeping.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    pub(crate) fn wake_one(&self) {
+        fence(Ordering::SeqCst);
+        if self.sleeping.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+
+        let sleeper = {
+            let mut sleepers = self.sleepers.lock().unwrap();
+            let sleeper = sleepers.pop();
+            if sleeper.is_some() {
+                self.sleeping.fetch_sub(1, Ordering::SeqCst);
+            }
+            sleeper
+        };
+
+        if let Some(sleeper) = sleeper {
+            sleeper.unpark();
+        }
+    }
+
+    pub(crate) fn wake_all(&self) {
+        let sleepers = {
+            let mut sleepers = self.sleepers.lock().unwrap();
+            self.sleeping.store(0, Ordering::SeqCst);
+            std::mem::take(&mut *sleepers)
+        };
+
+        for sleeper in sleepers {
+            sleeper.unpark();
+        }
+    }
+}
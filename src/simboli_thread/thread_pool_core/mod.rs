@@ -2,3 +2,11 @@ mod thread_pool_core;
 pub use thread_pool_core::ThreadPoolCore;
 
 mod thread_unit;
+pub(crate) use thread_unit::ThreadUnit;
+
+mod config;
+pub(crate) use config::{GroupTopology, PoolConfig};
+
+mod idle;
+pub(crate) use idle::IdleHandler;
+pub use idle::IdleStrategy;
@@ -1,46 +1,54 @@
 use std::{
     hint::spin_loop,
+    ops::Range,
     ptr::null_mut,
     sync::{
         Arc,
         atomic::{AtomicBool, AtomicPtr, AtomicU64, Ordering},
         mpsc,
     },
-    thread::{self, JoinHandle, sleep},
-    time::Duration,
+    thread,
 };
 
 use crate::{
-    ListCore, OutputTrait, TaskTrait, TaskWithDependenciesTrait, WaitingTask,
-    simboli_thread::thread_pool_core::thread_unit::ThreadUnit,
+    ListCore, OutputTrait, TaskOnceTrait, TaskWithDependenciesOnceTrait, WaitingTask,
+    simboli_thread::core::enter,
+    simboli_thread::thread_pool_core::{
+        PoolConfig,
+        thread_unit::{ThreadPool, ThreadUnit},
+    },
 };
 
-pub struct ThreadPoolCore<F, FD, O, const N: usize, const Q: usize>
+pub struct ThreadPoolCore<F, FD, O>
 where
-    F: TaskTrait<O> + 'static + Send,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
     // main thread pool
-    pub(crate) queue_size: usize,
-    pub(crate) pool: Arc<AtomicPtr<Vec<(Option<JoinHandle<()>>, Arc<ThreadUnit<F, FD, O, Q>>)>>>,
+    pub(crate) pool: Arc<AtomicPtr<ThreadPool<F, FD, O>>>,
 
     // handler
-    pub(crate) reprt_handler: Arc<AtomicBool>,
     pub(crate) done_task: Arc<AtomicU64>,
     pub(crate) join_flag: Arc<AtomicBool>,
 
+    // number of threads
+    pub(crate) threads: usize,
+
     // list core
     list_core: Arc<ListCore<F, FD, O>>,
 }
 
-impl<F, FD, O, const N: usize, const Q: usize> ThreadPoolCore<F, FD, O, N, Q>
+impl<F, FD, O> ThreadPoolCore<F, FD, O>
 where
-    F: TaskTrait<O> + 'static + Send,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: OutputTrait + Send,
 {
-    pub fn init(list_core: Arc<ListCore<F, FD, O>>) -> ThreadPoolCore<F, FD, O, N, Q> {
+    pub(crate) fn init(
+        list_core: Arc<ListCore<F, FD, O>>,
+        config: PoolConfig,
+    ) -> ThreadPoolCore<F, FD, O> {
         // handler
         let reprt_handler = Arc::new(AtomicBool::new(true));
         let join_flag = Arc::new(AtomicBool::new(false));
@@ -48,12 +56,11 @@ where
 
         // pool
         let pool = Arc::new(AtomicPtr::new(Box::into_raw(Box::new(Vec::with_capacity(
-            N,
+            config.threads,
         )))));
 
         // group
-        // // default for now
-        let size = 2;
+        let groups: Arc<[Range<usize>]> = config.group_ranges().into();
         let mut reprt_group_handler = Arc::new(AtomicBool::new(true));
         let mut start_harvesting_group: Arc<AtomicPtr<WaitingTask<F, FD, O>>> =
             Arc::new(AtomicPtr::new(null_mut()));
@@ -64,7 +71,7 @@ where
         let start_handler = Arc::new(AtomicBool::new(false));
         // MPSC
         let (tx, rx) = mpsc::channel();
-        for id in 0..N {
+        for id in 0..config.threads {
             // MPSC clone
             let tx_clone = tx.clone();
 
@@ -76,8 +83,8 @@ where
             let join_flag_clone = join_flag.clone();
             let reprt_handler_clone = reprt_handler.clone();
 
-            // reprt_group_handler, update every 2
-            if id % size == 0 {
+            // reprt_group_handler, update on the first thread of each group
+            if groups.iter().any(|group| group.start == id) {
                 reprt_group_handler = Arc::new(AtomicBool::new(true));
                 start_harvesting_group = Arc::new(AtomicPtr::new(null_mut()));
                 end_harvesting_group = Arc::new(AtomicPtr::new(null_mut()));
@@ -92,39 +99,50 @@ where
             // list_core clone
             let list_core_clone = list_core.clone();
 
+            // config clone
+            let config_clone = config.clone();
+            let groups_clone = groups.clone();
+
             // spawn thread
-            let spawn = thread::spawn(move || {
-                let thread_unit = Arc::new(
-                    ThreadUnit::<F, FD, O, Q>::init(
-                        id,
-                        N,
-                        reprt_handler_clone,
-                        join_flag_clone,
-                        done_task_clone,
-                        pool_clone,
-                        list_core_clone,
-                        reprt_group_handler_clone,
-                        start_harvesting_group_clone,
-                        end_harvesting_group_clone,
-                    )
-                    .unwrap(),
-                );
-
-                // give thread to thread pool
-                sleep(Duration::from_millis((1 * id) as u64));
-                tx_clone.send(thread_unit.clone()).unwrap();
-
-                // waiting
-                loop {
-                    let start_status = start_handler_clone.load(Ordering::SeqCst);
-                    if start_status {
-                        break;
+            let mut builder = thread::Builder::new();
+            if let Some(thread_name) = &config.thread_name {
+                builder = builder.name(format!("{}-{}", thread_name, id));
+            }
+            let spawn = builder
+                .spawn(move || {
+                    let thread_unit = Arc::new(
+                        ThreadUnit::<F, FD, O>::init(
+                            id,
+                            &config_clone,
+                            groups_clone,
+                            reprt_handler_clone,
+                            join_flag_clone,
+                            done_task_clone,
+                            pool_clone,
+                            list_core_clone,
+                            reprt_group_handler_clone,
+                            start_harvesting_group_clone,
+                            end_harvesting_group_clone,
+                        )
+                        .unwrap(),
+                    );
+
+                    // give thread to thread pool
+                    tx_clone.send(thread_unit.clone()).unwrap();
+
+                    // waiting
+                    loop {
+                        let start_status = start_handler_clone.load(Ordering::SeqCst);
+                        if start_status {
+                            break;
+                        }
                     }
-                }
 
-                // running
-                thread_unit.running();
-            });
+                    // running, tasks reach the thread unit with simboli_thread::current
+                    let _worker = enter(thread_unit.clone());
+                    thread_unit.running();
+                })
+                .expect("failed to spawn thread pool thread");
             // RX from MPSC
             let shared_thread = rx.recv().unwrap();
             // saving
@@ -139,19 +157,21 @@ where
         start_handler.store(true, Ordering::SeqCst);
 
         Self {
-            list_core: list_core,
-            pool: pool,
-            queue_size: Q,
-            reprt_handler: reprt_handler,
+            list_core,
+            pool,
             join_flag,
             done_task,
+            threads: config.threads,
         }
     }
 
     /// joining threads in thread pools, does not ensure that all tasks have completed execution before the thread stops
     pub fn join_directly(&self) {
         unsafe {
+            self.list_core.stop_periodic();
+            self.list_core.stop_timer();
             self.join_flag.store(true, Ordering::Release);
+            self.list_core.idle_handler.wake_all();
             for (join_handle, _) in (*self.pool.load(Ordering::Acquire)).iter_mut() {
                 join_handle.take().unwrap().join().unwrap();
             }
@@ -166,27 +186,23 @@ where
         }
     }
 
-    /// join threads in thread pools, but ensure all tasks have completed execution before the thread stops
+    /// join threads in thread pools, but ensure all tasks have completed execution before the thread stops.
+    /// periodic tasks are stopped first
     pub fn join(&self) {
         unsafe {
+            // no new run of periodic tasks
+            self.list_core.stop_periodic();
+
             // check, all task done
-            loop {
-                // println!(
-                //     "{} / {}",
-                //     self.list_core.in_task.load(Ordering::SeqCst),
-                //     self.done_task.load(Ordering::SeqCst)
-                // );
-                // sleep(Duration::from_millis(1000));
-                if self.list_core.in_task.load(Ordering::SeqCst)
-                    <= self.done_task.load(Ordering::SeqCst)
-                {
-                    break;
-                }
-                spin_loop();
-            }
+            self.wait_done_task();
+
+            // no delayed task is left, a periodic run may be released before the timer stops
+            self.list_core.stop_timer();
+            self.wait_done_task();
 
             // join
             self.join_flag.store(true, Ordering::Release);
+            self.list_core.idle_handler.wake_all();
             for (join_handle, _) in (*self.pool.load(Ordering::Acquire)).iter_mut() {
                 join_handle.take().unwrap().join().unwrap();
             }
@@ -200,4 +216,21 @@ where
             drop(Box::from_raw(pool_ptr));
         }
     }
+
+    fn wait_done_task(&self) {
+        loop {
+            // println!(
+            //     "{} / {}",
+            //     self.list_core.in_task.load(Ordering::SeqCst),
+            //     self.done_task.load(Ordering::SeqCst)
+            // );
+            // sleep(Duration::from_millis(1000));
+            if self.list_core.in_task.load(Ordering::SeqCst)
+                <= self.done_task.load(Ordering::SeqCst)
+            {
+                break;
+            }
+            spin_loop();
+        }
+    }
 }
@@ -1,22 +1,29 @@
 use std::{
     hint::spin_loop,
-    os::fd,
-    ptr::{null, null_mut},
+    ops::Range,
+    panic::{self, AssertUnwindSafe},
+    ptr::null_mut,
     sync::{
         Arc,
-        atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicU64, AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicPtr, AtomicU8, AtomicU32, AtomicU64, AtomicUsize, Ordering},
     },
     thread::{self, JoinHandle},
 };
 
 use crate::{
-    ExecTask, ListCore, OutputTrait, TaskTrait, TaskWithDependenciesTrait, Waiting, WaitingTask,
+    ExecTask, IdleStrategy, ListCore, OutputTrait, Priority, TaskDependenciesCore, TaskOnceTrait,
+    TaskPanic, TaskWithDependenciesOnceTrait, Waiting, WaitingError, WaitingTask,
+    simboli_thread::thread_pool_core::PoolConfig,
 };
 
-pub struct ThreadUnit<F, FD, O, const N: usize>
+pub(crate) type ThreadPool<F, FD, O> = Vec<(Option<JoinHandle<()>>, Arc<ThreadUnit<F, FD, O>>)>;
+
+pub(crate) type LocalQueue<F, FD, O> = Box<[AtomicPtr<WaitingTask<F, FD, O>>]>;
+
+pub struct ThreadUnit<F, FD, O>
 where
-    F: TaskTrait<O> + 'static + Send,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
     // thread
@@ -24,19 +31,25 @@ where
     pub(crate) id: usize,
     pub(crate) xorshift_seed: AtomicU32,
     // // engine
-    pub(crate) spawn: Option<JoinHandle<()>>,
     pub(crate) running: AtomicPtr<WaitingTask<F, FD, O>>,
     // // storage
-    pub(crate) queue: AtomicPtr<[AtomicPtr<WaitingTask<F, FD, O>>; N]>,
-    pub(crate) batch: u32,
+    pub(crate) queue: AtomicPtr<LocalQueue<F, FD, O>>,
+    pub(crate) batch: usize,
     pub(crate) top: AtomicUsize,
     pub(crate) bottom: AtomicUsize,
+    // // priority of the tasks in the local queue, index of Priority
+    pub(crate) priority: AtomicU8,
     // // flag
     pub(crate) threads_active: AtomicU64,
     pub(crate) empty_flag: AtomicBool,
     pub(crate) join_flag: Arc<AtomicBool>,
     pub(crate) done_task: Arc<AtomicU64>,
+    // // idle
+    pub(crate) idle_strategy: IdleStrategy,
     // group
+    pub(crate) group: Range<usize>,
+    pub(crate) groups: Arc<[Range<usize>]>,
+    pub(crate) harvest_interval: usize,
     pub(crate) reprt_group_handler: Arc<AtomicBool>,
     pub(crate) reprt_group_counter: AtomicUsize,
     pub(crate) start_l_waiting_list: AtomicPtr<WaitingTask<F, FD, O>>,
@@ -47,17 +60,17 @@ where
     // share
     // // thread_pool
     pub(crate) total_threads: usize,
-    pub(crate) pool: Arc<AtomicPtr<Vec<(Option<JoinHandle<()>>, Arc<ThreadUnit<F, FD, O, N>>)>>>,
+    pub(crate) pool: Arc<AtomicPtr<ThreadPool<F, FD, O>>>,
     pub(crate) reprt_handler: Arc<AtomicBool>,
 
     // // list core
     pub(crate) list_core: Arc<ListCore<F, FD, O>>,
 }
 
-impl<F, FD, O, const Q: usize> ThreadUnit<F, FD, O, Q>
+impl<F, FD, O> ThreadUnit<F, FD, O>
 where
-    F: TaskTrait<O> + 'static + Send,
-    FD: TaskWithDependenciesTrait<O> + Send + 'static,
+    F: TaskOnceTrait<O> + 'static + Send,
+    FD: TaskWithDependenciesOnceTrait<O> + Send + 'static,
     O: 'static + OutputTrait + Send,
 {
     pub fn clean(&self) {
@@ -81,51 +94,58 @@ where
         }
     }
 
-    pub fn init(
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn init(
         id: usize,
-        total_threads: usize,
+        config: &PoolConfig,
+        groups: Arc<[Range<usize>]>,
         reprt_handler: Arc<AtomicBool>,
         join_flag: Arc<AtomicBool>,
         done_task: Arc<AtomicU64>,
-        pool: Arc<AtomicPtr<Vec<(Option<JoinHandle<()>>, Arc<ThreadUnit<F, FD, O, Q>>)>>>,
+        pool: Arc<AtomicPtr<ThreadPool<F, FD, O>>>,
         list_core: Arc<ListCore<F, FD, O>>,
         reprt_group_handler: Arc<AtomicBool>,
         start_harvesting_group: Arc<AtomicPtr<WaitingTask<F, FD, O>>>,
         end_harvesting_group: Arc<AtomicPtr<WaitingTask<F, FD, O>>>,
-    ) -> Result<ThreadUnit<F, FD, O, Q>, &'static str> {
-        let mut queue_vector = Vec::with_capacity(Q);
-        for _ in 0..Q {
-            queue_vector.push(AtomicPtr::new(null_mut()));
+    ) -> Result<ThreadUnit<F, FD, O>, &'static str> {
+        if config.queue_capacity == 0 {
+            return Err("queue capacity must be greater than zero");
         }
 
-        let queue_ptr = Box::into_raw(Box::new(
-            queue_vector
-                .try_into()
-                .map_err(|_| "casting vector to array when creating error queue")?,
-        ));
+        let group = groups
+            .iter()
+            .find(|group| group.contains(&id))
+            .cloned()
+            .ok_or("thread is not part of any group")?;
+
+        let queue_ptr = Box::into_raw(Self::local_queue(Vec::new(), config.queue_capacity));
         let local_queue = AtomicPtr::new(queue_ptr);
 
         Ok(ThreadUnit {
             id,
             xorshift_seed: AtomicU32::new(1),
 
-            spawn: None,
             running: AtomicPtr::new(null_mut()),
 
             queue: local_queue,
-            batch: Q as u32,
+            batch: config.queue_capacity,
             bottom: AtomicUsize::new(0),
             top: AtomicUsize::new(0),
+            priority: AtomicU8::new(Priority::Normal.index() as u8),
 
             threads_active: AtomicU64::new(0),
             empty_flag: AtomicBool::new(true),
             join_flag,
             done_task,
+            idle_strategy: config.idle_strategy,
 
             reprt_handler,
             pool,
-            total_threads,
+            total_threads: config.threads,
 
+            group,
+            groups,
+            harvest_interval: config.harvest_interval,
             reprt_group_handler,
             reprt_group_counter: AtomicUsize::new(0),
             start_harvesting_group,
@@ -137,6 +157,16 @@ where
         })
     }
 
+    // the batch takes the first `batch` slots, tasks spawned by running tasks are pushed above it
+    fn local_queue(
+        list: Vec<AtomicPtr<WaitingTask<F, FD, O>>>,
+        batch: usize,
+    ) -> Box<LocalQueue<F, FD, O>> {
+        let mut list = list;
+        list.resize_with(batch * 2, || AtomicPtr::new(null_mut()));
+        Box::new(list.into_boxed_slice())
+    }
+
     fn xorshift(&self) -> u32 {
         let mut x = self.xorshift_seed.load(Ordering::Acquire);
         x ^= x << 13;
@@ -149,25 +179,20 @@ where
     fn harvesting(&self) -> Result<(), ()> {
         // add reprt_group counter
         let reprt_group_counter = self.reprt_group_counter.fetch_add(1, Ordering::Release);
-        if reprt_group_counter >= 32 {
+        if reprt_group_counter >= self.harvest_interval {
             // harvesting!
             let is_reprt_group = self.reprt_group_handler.swap(false, Ordering::AcqRel);
             if !is_reprt_group {
                 self.reprt_group_counter.store(0, Ordering::Release);
                 return Err(());
             }
-            let id = self.id as u64;
-            // size each group 2
-            let marking = !((1_u64 << 1) - 1);
-            let index = id & marking;
-
             unsafe {
                 let pool = &*self.pool.load(Ordering::Acquire);
-                for idx in index..index + 2 {
-                    if idx as usize >= pool.len() {
+                for idx in self.group.clone() {
+                    if idx >= pool.len() {
                         break;
                     }
-                    let (_, harvesting_target) = &pool[idx as usize];
+                    let (_, harvesting_target) = &pool[idx];
 
                     let end = harvesting_target
                         .end_l_waiting_list
@@ -199,10 +224,10 @@ where
         let start: AtomicPtr<WaitingTask<F, FD, O>> = AtomicPtr::new(null_mut());
         let end: AtomicPtr<WaitingTask<F, FD, O>> = AtomicPtr::new(null_mut());
 
-        // 2 is size of group(same reprt group)
-        for i in (0..self.total_threads).step_by(2) {
+        // first thread of each group(same reprt group)
+        for group in self.groups.iter() {
             unsafe {
-                let (_, group) = &(&*self.pool.load(Ordering::Acquire))[i];
+                let (_, group) = &(&*self.pool.load(Ordering::Acquire))[group.start];
 
                 let group_end = group
                     .end_harvesting_group
@@ -226,272 +251,440 @@ where
         self.list_core.insert_list_from_harvesting(start, end);
     }
 
+    // thread with more than one high priority task in its local queue
+    fn high_priority_target(&self) -> Option<&Arc<ThreadUnit<F, FD, O>>> {
+        let pool = unsafe { &*self.pool.load(Ordering::Acquire) };
+        pool.iter()
+            .map(|(_, thread_unit)| thread_unit)
+            .find(|thread_unit| {
+                thread_unit.id != self.id
+                    && thread_unit.priority.load(Ordering::Acquire) == Priority::High.index() as u8
+                    && !thread_unit.empty_flag.load(Ordering::SeqCst)
+                    && thread_unit.bottom.load(Ordering::Acquire)
+                        > thread_unit.top.load(Ordering::Acquire) + 1
+            })
+    }
+
+    fn has_pending_task(&self) -> bool {
+        if !self.list_core.is_swap_list_empty() || !self.list_core.is_primary_list_empty() {
+            return true;
+        }
+
+        unsafe {
+            let pool = &*self.pool.load(Ordering::Acquire);
+            pool.iter().any(|(_, thread_unit)| {
+                // released task, waiting for harvesting
+                !thread_unit.end_l_waiting_list.load(Ordering::Acquire).is_null()
+                    || !thread_unit.start_l_waiting_list.load(Ordering::Acquire).is_null()
+                    || !thread_unit.end_harvesting_group.load(Ordering::Acquire).is_null()
+                    || !thread_unit.start_harvesting_group.load(Ordering::Acquire).is_null()
+                    // able to steal
+                    || (thread_unit.id != self.id
+                        && !thread_unit.empty_flag.load(Ordering::SeqCst)
+                        && thread_unit
+                            .bottom
+                            .load(Ordering::Acquire)
+                            .saturating_sub(thread_unit.top.load(Ordering::Acquire))
+                            > 1)
+            })
+        }
+    }
+
+    fn idle(&self, idle_rounds: &mut u32) {
+        let spin_rounds = self.idle_strategy.spin_rounds;
+        let yield_rounds = spin_rounds.saturating_add(self.idle_strategy.yield_rounds);
+
+        if *idle_rounds < spin_rounds || self.has_pending_task() {
+            spin_loop();
+        } else if *idle_rounds < yield_rounds {
+            thread::yield_now();
+        } else {
+            self.list_core
+                .idle_handler
+                .park(self.idle_strategy.park_timeout, || {
+                    self.join_flag.load(Ordering::SeqCst) || self.has_pending_task()
+                });
+        }
+
+        *idle_rounds = idle_rounds.saturating_add(1);
+    }
+
     pub fn running(&self) {
+        let mut idle_rounds = 0;
         loop {
             let _ = self.harvesting();
             // if let Err(_) = status {
             //     continue;
             // }
 
-            // is local queue empty?
-            if self.top.load(Ordering::Acquire) >= self.bottom.load(Ordering::Acquire) {
-                // check join
-                if self.join_flag.load(Ordering::SeqCst) {
-                    break;
-                }
+            // done your work
+            if let Some(waiting_task) = self.pop_local() {
+                idle_rounds = 0;
+                self.run_waiting_task(waiting_task);
+                continue;
+            }
 
-                // empty handling
-                // // update flag
-                self.empty_flag.store(true, Ordering::SeqCst);
+            // local queue empty
+            // check join
+            if self.join_flag.load(Ordering::SeqCst) {
+                break;
+            }
 
-                // // check, any threads have activities on this thread?
-                if self.threads_active.load(Ordering::SeqCst) > 0 {
-                    // activities detected
-                    spin_loop();
-                    continue;
-                };
-                // // check representative thread handler
-                let is_representative_thread = (*self.reprt_handler).swap(false, Ordering::SeqCst);
-
-                if is_representative_thread {
-                    // now, this thread as representative thread
-                    // // check primary list
-                    if (*self.list_core).is_primary_list_empty() {
-                        // empty, swap waiting_task with swap list
-                        if let Err(_) = (*self.list_core).swap_to_primary() {
-                            // this None, mean empty
-                            // get task from harvesting
-                            self.reprt_thread_harvesting();
-                            // release representative thread
-                            (*self.reprt_handler).store(true, Ordering::SeqCst);
-                            spin_loop();
-                            continue;
-                        }
-                    }
-                    // get task from harvesting
-                    self.reprt_thread_harvesting();
-
-                    // get waiting_task from primary_list
-                    let list_waiting_task = if let Ok(list) =
-                        (*self.list_core).get_waiting_task_from_primary_stack::<Q>(self.batch)
-                    {
-                        list
-                    } else {
-                        (*self.reprt_handler).store(true, Ordering::SeqCst);
-                        spin_loop();
-                        continue;
-                    };
+            // spin, yield or park while waiting
+            self.idle(&mut idle_rounds);
 
-                    // update local queue
-                    // // check twice to ensure, any threads have activities on this thread?
-                    while self.threads_active.load(Ordering::SeqCst) > 0 {
-                        spin_loop();
-                        // continue;
-                    }
+            let _ = self.fill_local_queue();
+        }
+    }
 
-                    let update_candidate_ptr = Box::into_raw(Box::new(list_waiting_task.list));
-                    let old_addr = self.queue.swap(update_candidate_ptr, Ordering::AcqRel);
-                    unsafe {
-                        drop(Box::from_raw(old_addr));
-                    }
+    // newest task of the local queue, tasks spawned by the running task first
+    pub(crate) fn pop_local(&self) -> Option<*mut WaitingTask<F, FD, O>> {
+        loop {
+            // get bottom
+            let bottom = self.bottom.load(Ordering::Acquire);
+            if self.top.load(Ordering::Acquire) >= bottom {
+                return None;
+            }
 
-                    // update top and bottom
-                    self.top
-                        .store(list_waiting_task.top as usize, Ordering::Release);
-                    self.bottom
-                        .store(list_waiting_task.bottom as usize, Ordering::Release);
+            // get waiting task
+            let waiting_task = unsafe {
+                (*self.queue.load(Ordering::Acquire))[bottom - 1].swap(null_mut(), Ordering::AcqRel)
+            };
+            // next index to top
+            self.bottom.fetch_sub(1, Ordering::Release);
 
-                    // release representative thread
-                    (*self.reprt_handler).store(true, Ordering::SeqCst);
-                    // update empty_flag
-                    self.empty_flag.store(false, Ordering::SeqCst);
+            // null, taken by a stealing thread
+            if !waiting_task.is_null() {
+                return Some(waiting_task);
+            }
+        }
+    }
 
-                    spin_loop();
-                } else {
-                    // if no, be steal mode
-                    unsafe {
-                        let target_thread = loop {
-                            // get random id
-                            let random = self.xorshift() as usize % self.total_threads;
-                            let (_, target_thread) = &(&*self.pool.load(Ordering::Acquire))[random];
-                            if target_thread.id == self.id {
-                                continue;
-                            }
-                            break target_thread;
-                        };
-
-                        // add activities(knoking the door) to target thread
-                        target_thread.threads_active.fetch_add(1, Ordering::SeqCst);
-                        // is thread able to steal
-                        if target_thread.empty_flag.load(Ordering::SeqCst) {
-                            // this target queue empty
-                            // close the door
-                            target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
-                            spin_loop();
-                            continue;
-                        };
-
-                        // get top and bottom
-                        let top = target_thread.top.load(Ordering::Acquire);
-                        let bottom = target_thread.bottom.load(Ordering::Acquire);
-                        // check
-                        if top >= bottom {
-                            // this thread literely empty
-                            // close the door
-                            target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
-                            spin_loop();
-                            continue;
-                        }
-                        // get distence
-                        let size = bottom - top;
-                        if size <= 1 {
-                            // close the door
-                            target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
-                            spin_loop();
-                            continue;
-                        }
-                        // get half
-                        let size = size / 2;
-                        let new_top = top + size;
-                        let status = target_thread.top.compare_exchange(
-                            top,
-                            new_top,
-                            Ordering::AcqRel,
-                            Ordering::Acquire,
-                        );
-
-                        if let Err(_) = status {
-                            // close the door
-                            target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
-                            spin_loop();
-                            continue;
-                        }
-                        // validation the task
-                        // check empty
-                        if target_thread.empty_flag.load(Ordering::SeqCst) {
-                            // this target tree empty
-                            // close the door
-                            target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
-                            spin_loop();
-                            continue;
-                        };
-
-                        // get task
-                        // // scanning start from "end"
-                        // // create template
-                        let mut list_waiting_task = Vec::new();
-                        for _ in 0..Q {
-                            list_waiting_task.push(AtomicPtr::new(null_mut()));
-                        }
+    // only the thread of this thread unit pushes, false when the local queue is full
+    pub(crate) fn push_local(&self, waiting_task: *mut WaitingTask<F, FD, O>) -> bool {
+        let top = self.top.load(Ordering::Acquire);
+        // a stealing thread can move top over bottom
+        let index = self.bottom.load(Ordering::Acquire).max(top);
+        let queue = unsafe { &*self.queue.load(Ordering::Acquire) };
+        if index >= queue.len() {
+            return false;
+        }
 
-                        let mut list_waiting_task: [AtomicPtr<WaitingTask<F, FD, O>>; Q] =
-                            list_waiting_task.try_into().unwrap();
+        queue[index].store(waiting_task, Ordering::Release);
+        self.bottom.store(index + 1, Ordering::Release);
+        self.empty_flag.store(false, Ordering::SeqCst);
 
-                        // // check every task
-                        let mut out_of_index_counter = false;
-                        let mut count = 0;
-                        for index in top..new_top {
-                            // is out of index?
-                            if index >= self.batch as usize {
-                                // out of index
-                                out_of_index_counter = true;
-                                break;
-                            }
+        // more than one task, wake a parked worker to steal
+        if index > top {
+            self.list_core.idle_handler.wake_one();
+        }
 
-                            let task = (*target_thread.queue.load(Ordering::Acquire))[index]
-                                .swap(null_mut(), Ordering::AcqRel);
+        true
+    }
 
-                            // is task valid?
-                            if task.is_null() {
-                                continue;
-                            }
+    pub(crate) fn run_waiting_task(&self, waiting_task: *mut WaitingTask<F, FD, O>) {
+        // running the task, get the data
+        let WaitingTask {
+            id,
+            task,
+            waiting_return_ptr,
+            task_dependencies_core_ptr,
+            task_dependencies_ptr,
+            ..
+        } = *unsafe { Box::from_raw(waiting_task) };
+
+        match task {
+            // poll of a spawned future, it stores the output once it is ready
+            ExecTask::Future(future_task) => future_task.poll(),
+            // continuation of a `Waiting`, it reads the output of its parent itself
+            ExecTask::Continuation(continuation_task) if waiting_return_ptr.try_start() => {
+                continuation_task.run(id, &waiting_return_ptr)
+            }
+            // cancelled before running, the output is already stored
+            task if waiting_return_ptr.try_start() => {
+                // running the task, execute the data
+                let dependencies = task_dependencies_ptr.as_deref().map_or(&[][..], |v| v);
+                let running = self.execute(id, task, dependencies);
+
+                // running the task, update return
+                waiting_return_ptr.store(running);
+            }
+            _ => {}
+        }
 
-                            list_waiting_task[(Q - 1) - count] = AtomicPtr::new(task);
+        // dependencies handler
+        let _ = self.dependencies_handler_type_2(task_dependencies_core_ptr);
 
-                            count += 1;
-                        }
+        // update counter
+        self.done_task.fetch_add(1, Ordering::SeqCst);
+    }
 
-                        // // out of index?
-                        if out_of_index_counter {
-                            // out of index, mean the range not valid
-                            // close the door
-                            target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
-                            spin_loop();
-                            continue;
-                        }
+    // local queue is empty, take tasks from the primary list as representative thread or steal them
+    pub(crate) fn fill_local_queue(&self) -> bool {
+        // empty handling
+        // // update flag
+        self.empty_flag.store(true, Ordering::SeqCst);
+
+        // // check, any threads have activities on this thread?
+        if self.threads_active.load(Ordering::SeqCst) > 0 {
+            // activities detected
+            spin_loop();
+            return false;
+        };
+        // // check representative thread handler
+        let is_representative_thread = (*self.reprt_handler).swap(false, Ordering::SeqCst);
+
+        if is_representative_thread {
+            self.take_from_primary_list()
+        } else {
+            self.steal()
+        }
+    }
 
-                        // valid, saving
-                        // update local queue
-                        // // check to ensure, any threads have activities on this thread?
-                        if self.threads_active.load(Ordering::SeqCst) > 0 {
-                            // activities detected
-                            spin_loop();
-                            continue;
-                        };
-                        let update_candidate_ptr = Box::into_raw(Box::new(list_waiting_task));
-                        let old_addr = self.queue.swap(update_candidate_ptr, Ordering::AcqRel);
-                        drop(Box::from_raw(old_addr));
-
-                        // update top and bottom
-                        self.top.store(Q - count, Ordering::Release);
-                        self.bottom.store(Q, Ordering::Release);
-
-                        // update empty_flag
-                        self.empty_flag.store(false, Ordering::SeqCst);
-
-                        // close the door
-                        target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
-                        spin_loop();
-                    }
-                }
+    fn take_from_primary_list(&self) -> bool {
+        // now, this thread as representative thread
+        // // swap waiting_task with swap list, for every empty priority level
+        if (*self.list_core).swap_to_primary().is_err() {
+            // this None, mean empty
+            // get task from harvesting
+            self.reprt_thread_harvesting();
+            // release representative thread
+            (*self.reprt_handler).store(true, Ordering::SeqCst);
+            spin_loop();
+            return false;
+        }
+        // get task from harvesting
+        self.reprt_thread_harvesting();
+
+        // get waiting_task from primary_list
+        let list_waiting_task =
+            if let Ok(list) = (*self.list_core).get_waiting_task_from_primary_stack(self.batch) {
+                list
             } else {
-                // done your work
-                unsafe {
-                    // get bottom
-                    let bottom = self.bottom.load(Ordering::Acquire);
-                    if bottom == 0 {
-                        // empty handling
-                        // // update flag
-                        self.empty_flag.store(true, Ordering::SeqCst);
-                    }
+                (*self.reprt_handler).store(true, Ordering::SeqCst);
+                spin_loop();
+                return false;
+            };
+
+        // update local queue
+        // // check twice to ensure, any threads have activities on this thread?
+        while self.threads_active.load(Ordering::SeqCst) > 0 {
+            spin_loop();
+            // continue;
+        }
 
-                    // get waiting task
-                    let waiting_task = (*self.queue.load(Ordering::Acquire))[bottom - 1]
-                        .swap(null_mut(), Ordering::AcqRel);
-                    if !waiting_task.is_null() {
-                        // running the task, get the data
-                        let task = Box::from_raw(waiting_task);
-
-                        // running the task, execute the data
-                        let running = match &task.task {
-                            ExecTask::Task(f) => f.exec(),
-                            ExecTask::TaskWithDependencies(f) => f.exec(task.task_dependencies_ptr),
-                            _ => panic!(),
-                        };
-
-                        // running the task, update return
-                        let output = Box::into_raw(Box::new(running));
-                        task.waiting_return_ptr.store(output, Ordering::Release);
-
-                        // dependencies handler
-                        let _ = self.dependencies_handler_type_2(task);
-
-                        // update counter
-                        self.done_task.fetch_add(1, Ordering::SeqCst);
+        let update_candidate_ptr = Box::into_raw(Self::local_queue(
+            list_waiting_task.list.into_vec(),
+            self.batch,
+        ));
+        let old_addr = self.queue.swap(update_candidate_ptr, Ordering::AcqRel);
+        unsafe {
+            drop(Box::from_raw(old_addr));
+        }
+
+        // update top and bottom
+        self.priority
+            .store(list_waiting_task.priority.index() as u8, Ordering::Release);
+        self.top
+            .store(list_waiting_task.top as usize, Ordering::Release);
+        self.bottom
+            .store(list_waiting_task.bottom as usize, Ordering::Release);
+
+        // release representative thread
+        (*self.reprt_handler).store(true, Ordering::SeqCst);
+        // update empty_flag
+        self.empty_flag.store(false, Ordering::SeqCst);
+
+        // more than one task, wake a parked worker to steal
+        if list_waiting_task.bottom - list_waiting_task.top > 1 {
+            self.list_core.idle_handler.wake_one();
+        }
+
+        spin_loop();
+        true
+    }
+
+    fn steal(&self) -> bool {
+        // no other thread to steal from
+        if self.total_threads < 2 {
+            spin_loop();
+            return false;
+        }
+
+        // if no, be steal mode
+        unsafe {
+            // high priority tasks first, otherwise a random thread
+            let target_thread = if let Some(target_thread) = self.high_priority_target() {
+                target_thread
+            } else {
+                loop {
+                    // get random id
+                    let random = self.xorshift() as usize % self.total_threads;
+                    let (_, target_thread) = &(&*self.pool.load(Ordering::Acquire))[random];
+                    if target_thread.id == self.id {
+                        continue;
                     }
+                    break target_thread;
+                }
+            };
+
+            // add activities(knoking the door) to target thread
+            target_thread.threads_active.fetch_add(1, Ordering::SeqCst);
+            // is thread able to steal
+            if target_thread.empty_flag.load(Ordering::SeqCst) {
+                // this target queue empty
+                // close the door
+                target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
+                spin_loop();
+                return false;
+            };
+
+            // get top and bottom
+            let top = target_thread.top.load(Ordering::Acquire);
+            let bottom = target_thread.bottom.load(Ordering::Acquire);
+            // check
+            if top >= bottom {
+                // this thread literely empty
+                // close the door
+                target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
+                spin_loop();
+                return false;
+            }
+            // get distence
+            let size = bottom - top;
+            if size <= 1 {
+                // close the door
+                target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
+                spin_loop();
+                return false;
+            }
+            // get half
+            let size = size / 2;
+            let new_top = top + size;
+            let status = target_thread.top.compare_exchange(
+                top,
+                new_top,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            );
+
+            if status.is_err() {
+                // close the door
+                target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
+                spin_loop();
+                return false;
+            }
+            // the range [top, new_top) belongs to this thread now, taken tasks are null
+            // get task
+            // // scanning start from "end"
+            // // create template
+            let mut list_waiting_task = Vec::with_capacity(self.batch);
+            for _ in 0..self.batch {
+                list_waiting_task.push(AtomicPtr::new(null_mut()));
+            }
+
+            // // check every task
+            let mut out_of_index_counter = false;
+            let mut count = 0;
+            let target_queue = &*target_thread.queue.load(Ordering::Acquire);
+            for index in top..new_top {
+                // is out of index?
+                if index >= target_queue.len() {
+                    // out of index
+                    out_of_index_counter = true;
+                    break;
+                }
+
+                let task = target_queue[index].swap(null_mut(), Ordering::AcqRel);
+
+                // is task valid?
+                if task.is_null() {
+                    continue;
+                }
+
+                list_waiting_task[(self.batch - 1) - count] = AtomicPtr::new(task);
+
+                count += 1;
+            }
 
-                    // next index to top
-                    self.bottom.fetch_sub(1, Ordering::Release);
+            // // out of index?
+            if out_of_index_counter {
+                // out of index, mean the range not valid
+                // close the door
+                target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
+                spin_loop();
+                return false;
+            }
+
+            // valid, saving
+            // update local queue
+            // // wait for any threads having activities on this thread, the stolen tasks must not be dropped
+            while self.threads_active.load(Ordering::SeqCst) > 0 {
+                spin_loop();
+            }
+            let update_candidate_ptr =
+                Box::into_raw(Self::local_queue(list_waiting_task, self.batch));
+            let old_addr = self.queue.swap(update_candidate_ptr, Ordering::AcqRel);
+            drop(Box::from_raw(old_addr));
+
+            // update top and bottom
+            self.priority.store(
+                target_thread.priority.load(Ordering::Acquire),
+                Ordering::Release,
+            );
+            self.top.store(self.batch - count, Ordering::Release);
+            self.bottom.store(self.batch, Ordering::Release);
+
+            // update empty_flag
+            self.empty_flag.store(false, Ordering::SeqCst);
+
+            // close the door
+            target_thread.threads_active.fetch_sub(1, Ordering::SeqCst);
+
+            // more than one task, wake a parked worker to steal
+            if count > 1 {
+                self.list_core.idle_handler.wake_one();
+            }
+            spin_loop();
+            true
+        }
+    }
+
+    fn execute(
+        &self,
+        id: u64,
+        task: ExecTask<F, FD, O>,
+        dependencies: &[Waiting<O>],
+    ) -> Result<O, WaitingError> {
+        // dependency failed or cancelled, the task is not executed
+        for dependency in dependencies {
+            match dependency.error() {
+                Some(WaitingError::Panicked(task_panic))
+                | Some(WaitingError::DependencyFailed(task_panic)) => {
+                    return Err(WaitingError::DependencyFailed(task_panic.clone()));
                 }
+                // dependency cancelled, cancel this task too
+                Some(WaitingError::Cancelled) => return Err(WaitingError::Cancelled),
+                _ => {}
             }
         }
+
+        // catch panic, keep the worker alive
+        panic::catch_unwind(AssertUnwindSafe(|| match task {
+            ExecTask::Task(f) => f.exec_once(),
+            ExecTask::TaskWithDependencies(f) => f.exec_once(dependencies),
+            _ => panic!(),
+        }))
+        .map_err(|payload| WaitingError::Panicked(TaskPanic::from_payload(id, payload)))
     }
 
-    pub fn dependencies_handler_type_2(&self, task: Box<WaitingTask<F, FD, O>>) -> Result<(), ()> {
-        if task.task_dependencies_core_ptr.status {
+    pub fn dependencies_handler_type_2(
+        &self,
+        task_dependencies_core_ptr: Option<Arc<TaskDependenciesCore<F, FD, O>>>,
+    ) -> Result<(), ()> {
+        if let Some(task_dependencies_core_ptr) = task_dependencies_core_ptr {
             // update counter
-            let counter = task
-                .task_dependencies_core_ptr
+            let counter = task_dependencies_core_ptr
                 .counter
                 .fetch_sub(1, Ordering::Release);
 
@@ -500,21 +693,16 @@ where
             }
 
             // update done flag
-            task.task_dependencies_core_ptr
+            task_dependencies_core_ptr
                 .done
                 .store(true, Ordering::Release);
 
-            let check_task = task
-                .task_dependencies_core_ptr
-                .start
-                .load(Ordering::Acquire);
+            let check_task = task_dependencies_core_ptr.start.load(Ordering::Acquire);
             if !check_task.is_null() {
                 // CAS RETRY LOOP
                 let start_waiting_task = loop {
-                    let status = task.task_dependencies_core_ptr.start.compare_exchange(
-                        task.task_dependencies_core_ptr
-                            .start
-                            .load(Ordering::Acquire),
+                    let status = task_dependencies_core_ptr.start.compare_exchange(
+                        task_dependencies_core_ptr.start.load(Ordering::Acquire),
                         null_mut(),
                         Ordering::AcqRel,
                         Ordering::Acquire,
@@ -528,8 +716,7 @@ where
                     }
                 };
 
-                let end_waiting_task = task
-                    .task_dependencies_core_ptr
+                let end_waiting_task = task_dependencies_core_ptr
                     .end
                     .swap(null_mut(), Ordering::Acquire);
 
@@ -550,8 +737,6 @@ where
             }
         }
 
-        drop(task);
-
         Ok(())
     }
 }
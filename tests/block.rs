@@ -0,0 +1,83 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{sleep, yield_now},
+    time::{Duration, Instant},
+};
+
+use simboli_thread::{ClosureThread, WaitingError};
+
+#[test]
+fn block_timeout_returns_timeout_while_the_task_runs() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let waiting = thread_pool.spawn(|| {
+        sleep(Duration::from_millis(300));
+        7
+    });
+
+    let start = Instant::now();
+    assert_eq!(
+        waiting.block_timeout(Duration::from_millis(20)),
+        Err(WaitingError::Timeout)
+    );
+    assert!(start.elapsed() < Duration::from_millis(300));
+
+    // the task keeps running, a later block gets the output
+    assert_eq!(waiting.block_timeout(Duration::from_secs(10)), Ok(&7));
+    thread_pool.join();
+}
+
+#[test]
+fn block_deadline_in_the_past_only_checks_the_output() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let slow = thread_pool.spawn(|| {
+        sleep(Duration::from_millis(200));
+        1
+    });
+    assert_eq!(
+        slow.block_deadline(Instant::now()),
+        Err(WaitingError::Timeout)
+    );
+
+    let fast = thread_pool.spawn(|| 2);
+    fast.block().unwrap();
+    assert_eq!(fast.block_deadline(Instant::now()), Ok(&2));
+    thread_pool.join();
+}
+
+#[test]
+fn block_timeout_too_large_for_a_deadline_blocks() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let waiting = thread_pool.spawn(|| 3);
+    assert_eq!(waiting.block_timeout(Duration::MAX), Ok(&3));
+    thread_pool.join();
+}
+
+#[test]
+fn get_is_none_until_the_output_is_stored() {
+    let thread_pool = ClosureThread::<u64>::builder().threads(1).build();
+    let open = Arc::new(AtomicBool::new(false));
+    let gate_open = open.clone();
+    let gate = thread_pool.spawn(move || {
+        while !gate_open.load(Ordering::SeqCst) {
+            yield_now();
+        }
+        1
+    });
+    // queued behind the gate on the only worker
+    let queued = thread_pool.spawn(|| 2);
+
+    for waiting in [&gate, &queued] {
+        assert_eq!(waiting.get(), None);
+        assert!(!waiting.is_ready());
+    }
+
+    open.store(true, Ordering::SeqCst);
+    assert_eq!(queued.block(), Ok(&2));
+    assert!(gate.is_ready() && queued.is_ready());
+    assert_eq!(gate.get(), Some(&1));
+    assert_eq!(queued.get(), Some(&2));
+    thread_pool.join();
+}
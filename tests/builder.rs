@@ -0,0 +1,110 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use simboli_thread::{ClosureThread, Waiting};
+
+// levels of dependency groups, each level is released by the harvest of the one before
+fn dependency_levels(thread_pool: &ClosureThread<u64>) {
+    for round in 0..20u64 {
+        let load = thread_pool.spawn_dependencies((0..8u64).map(move |k| move || round + k));
+        let transform = thread_pool.spawn_dependencies_with_dependencies(
+            (0..4u64).map(|k| {
+                move |dependencies: &[Waiting<u64>]| {
+                    dependencies
+                        .iter()
+                        .map(|waiting| *waiting.get().unwrap())
+                        .sum::<u64>()
+                        + k
+                }
+            }),
+            &load,
+        );
+        let reduce = thread_pool.spawn_with_dependencies(
+            |dependencies: &[Waiting<u64>]| {
+                dependencies
+                    .iter()
+                    .map(|waiting| *waiting.get().unwrap())
+                    .sum()
+            },
+            &transform,
+        );
+
+        let loaded = 8 * round + 28;
+        assert_eq!(
+            reduce.block_timeout(Duration::from_secs(10)),
+            Ok(&(4 * loaded + 6))
+        );
+    }
+}
+
+#[test]
+fn remainder_group_releases_dependent_tasks() {
+    let thread_pool = ClosureThread::<u64>::builder()
+        .threads(5)
+        .group_size(2)
+        .queue_capacity(2)
+        .build();
+    dependency_levels(&thread_pool);
+    thread_pool.join();
+}
+
+#[test]
+fn threads_are_named_after_thread_name() {
+    let thread_pool = ClosureThread::<String>::builder()
+        .threads(3)
+        .thread_name("builder-test")
+        .build();
+    let names = Arc::new(Mutex::new(HashSet::new()));
+    let waitings: Vec<_> = (0..64)
+        .map(|_| {
+            let names = names.clone();
+            thread_pool.spawn(move || {
+                let name = thread::current().name().unwrap().to_string();
+                names.lock().unwrap().insert(name.clone());
+                name
+            })
+        })
+        .collect();
+    thread_pool.join();
+
+    for waiting in waitings.iter() {
+        assert!(waiting.get().unwrap().starts_with("builder-test-"));
+    }
+    let expected: HashSet<String> = (0..3).map(|id| format!("builder-test-{}", id)).collect();
+    assert!(names.lock().unwrap().is_subset(&expected));
+}
+
+#[test]
+fn cluster_groups_release_dependent_tasks() {
+    let thread_pool = ClosureThread::<u64>::builder()
+        .clusters([3, 1])
+        .queue_capacity(2)
+        .build();
+    dependency_levels(&thread_pool);
+    thread_pool.join();
+}
+
+#[test]
+#[should_panic(expected = "clusters have 4 threads in total, the thread pool has 5 threads")]
+fn clusters_must_match_the_number_of_threads() {
+    let _ = ClosureThread::<u64>::builder()
+        .clusters([3, 1])
+        .threads(5)
+        .build();
+}
+
+#[test]
+fn harvest_interval_releases_dependent_tasks() {
+    for harvest_interval in [0, 1, 1000] {
+        let thread_pool = ClosureThread::<u64>::builder()
+            .threads(4)
+            .harvest_interval(harvest_interval)
+            .build();
+        dependency_levels(&thread_pool);
+        thread_pool.join();
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread::{sleep, yield_now},
+    time::Duration,
+};
+
+use simboli_thread::{CancellationToken, ClosureTask, ClosureThread, Waiting, WaitingError};
+
+// keeps the only worker busy until the gate opens
+fn busy_worker(thread_pool: &ClosureThread<u64>, gate: &Arc<AtomicBool>) -> Waiting<u64> {
+    let gate = gate.clone();
+    thread_pool.spawn(move || {
+        while !gate.load(Ordering::SeqCst) {
+            yield_now();
+        }
+        0
+    })
+}
+
+#[test]
+fn cancel_reaches_spawn_after_dependents() {
+    let thread_pool = ClosureThread::<u64>::builder().threads(1).build();
+    let gate = Arc::new(AtomicBool::new(false));
+    let runs = Arc::new(AtomicUsize::new(0));
+    let busy = busy_worker(&thread_pool, &gate);
+
+    let counter = runs.clone();
+    let parent = thread_pool.spawn(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        1
+    });
+    let counter = runs.clone();
+    let child = thread_pool.spawn_after(
+        &[&parent],
+        ClosureTask::with_dependencies(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            2
+        }),
+    );
+    let counter = runs.clone();
+    let grandchild = thread_pool.spawn_after(
+        &[&child],
+        ClosureTask::with_dependencies(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            3
+        }),
+    );
+
+    assert!(parent.cancel());
+    gate.store(true, Ordering::SeqCst);
+    busy.collect().unwrap();
+
+    assert_eq!(parent.block(), Err(WaitingError::Cancelled));
+    assert_eq!(child.block(), Err(WaitingError::Cancelled));
+    assert_eq!(grandchild.block(), Err(WaitingError::Cancelled));
+    thread_pool.join();
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn token_cancel_reaches_task_dependencies() {
+    let thread_pool = ClosureThread::<u64>::builder().threads(1).build();
+    let gate = Arc::new(AtomicBool::new(false));
+    let runs = Arc::new(AtomicUsize::new(0));
+    let busy = busy_worker(&thread_pool, &gate);
+
+    let first = thread_pool.spawn_dependencies([|| 1, || 2]);
+    let counter = runs.clone();
+    let second = thread_pool.spawn_dependencies_with_dependencies(
+        [move |_: &[Waiting<u64>]| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            3
+        }],
+        &first,
+    );
+    let counter = runs.clone();
+    let last = thread_pool.spawn_with_dependencies(
+        move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            4
+        },
+        &second,
+    );
+
+    let token = CancellationToken::new();
+    for waiting in first.waiting_list.iter() {
+        token.register(waiting);
+    }
+    token.cancel();
+    gate.store(true, Ordering::SeqCst);
+    busy.collect().unwrap();
+
+    assert_eq!(last.block(), Err(WaitingError::Cancelled));
+    thread_pool.join();
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn running_task_is_not_cancelled() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let token = CancellationToken::new();
+    let polled = token.clone();
+    let started = Arc::new(AtomicBool::new(false));
+    let is_started = started.clone();
+    let running = thread_pool.spawn(move || {
+        is_started.store(true, Ordering::SeqCst);
+        let mut polls = 0;
+        while !polled.is_cancelled() {
+            polls += 1;
+            sleep(Duration::from_millis(1));
+        }
+        polls
+    });
+    token.register(&running);
+    while !started.load(Ordering::SeqCst) {
+        yield_now();
+    }
+    token.cancel();
+
+    // the task saw the token and returned its own output
+    assert!(running.collect().is_ok());
+
+    let late = thread_pool.spawn(|| 9);
+    late.block().unwrap();
+    assert!(!late.cancel());
+    assert_eq!(late.collect(), Ok(9));
+    thread_pool.join();
+}
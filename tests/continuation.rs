@@ -0,0 +1,134 @@
+use std::{thread::sleep, time::Duration};
+
+use simboli_thread::{AnyThread, ClosureThread, WaitingError};
+
+fn slow(output: u64) -> impl FnOnce() -> u64 + Send + 'static {
+    move || {
+        sleep(Duration::from_millis(10));
+        output
+    }
+}
+
+#[test]
+fn continuations_chain_outputs() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let parent = thread_pool.spawn(slow(20));
+    let chained = parent.map(|x| x + 1).map(|x| x * 2).map(|x| x.to_string());
+    assert_eq!(chained.block().unwrap(), "42");
+    assert_eq!(
+        parent.then(|output| output.copied().unwrap_or(0)).collect(),
+        Ok(20)
+    );
+    assert_eq!(parent.and_then(|x| Ok(*x * 3)).collect(), Ok(60));
+
+    let mut waiting = thread_pool.spawn(|| 0);
+    for _ in 0..500 {
+        waiting = waiting.map(|x| x + 1);
+    }
+    assert_eq!(waiting.collect(), Ok(500));
+    thread_pool.join();
+}
+
+#[test]
+fn parent_panic_is_a_dependency_failure() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let parent = thread_pool.spawn(|| panic!("parent panic"));
+
+    let mapped = parent.map(|x| x + 1);
+    assert!(matches!(
+        mapped.block(),
+        Err(WaitingError::DependencyFailed(_))
+    ));
+    // stays a dependency failure further down the chain
+    assert!(matches!(
+        mapped.map(|x| x + 1).block(),
+        Err(WaitingError::DependencyFailed(_))
+    ));
+    assert!(matches!(
+        parent.and_then(|x| Ok(*x)).block(),
+        Err(WaitingError::DependencyFailed(_))
+    ));
+    // then sees the failure of the parent itself
+    assert_eq!(
+        parent
+            .then(|output| matches!(output, Err(WaitingError::Panicked(_))))
+            .collect(),
+        Ok(true)
+    );
+    thread_pool.join();
+}
+
+#[test]
+fn continuation_failures() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let parent = thread_pool.spawn(slow(20));
+
+    let failed = parent.and_then(|_| -> Result<u64, WaitingError> { Err(WaitingError::Cancelled) });
+    assert_eq!(failed.block(), Err(WaitingError::Cancelled));
+
+    let panicked = parent.map(|_| -> u64 { panic!("continuation panic") });
+    match panicked.block() {
+        Err(WaitingError::Panicked(task_panic)) => {
+            assert_eq!(task_panic.id(), panicked.id());
+            assert_eq!(task_panic.message(), "continuation panic");
+        }
+        _ => panic!("panic expected"),
+    }
+    thread_pool.join();
+}
+
+#[test]
+fn cancel_parent_or_continuation() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let delayed = thread_pool.spawn_after_delay(Duration::from_millis(100), || 1);
+    let mapped = delayed.map(|x| x + 1);
+    assert!(delayed.cancel());
+    assert_eq!(mapped.block(), Err(WaitingError::Cancelled));
+
+    let parent = thread_pool.spawn(slow(1));
+    let continuation = parent.map(|x| x + 1);
+    assert!(continuation.cancel());
+    assert_eq!(continuation.block(), Err(WaitingError::Cancelled));
+    assert_eq!(parent.collect(), Ok(1));
+    thread_pool.join();
+}
+
+#[test]
+fn join_waits_for_continuations() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let late = thread_pool.spawn(slow(1)).map(|x| x + 1);
+    thread_pool.join();
+    assert_eq!(late.get(), Some(&2));
+}
+
+#[test]
+fn continuation_of_a_typed_output() {
+    let thread_pool = AnyThread::closures();
+    let length = thread_pool
+        .spawn_typed(|| "abc".to_string())
+        .map(String::len);
+    assert_eq!(length.collect(), Ok(3));
+    thread_pool.join();
+}
+
+#[test]
+fn continuations_run_in_place_after_join() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let parent = thread_pool.spawn(slow(20));
+    let failed = thread_pool.spawn(|| panic!("failed"));
+    thread_pool.join();
+
+    assert_eq!(parent.map(|x| x + 1).collect(), Ok(21));
+    assert_eq!(
+        parent.and_then(|x| Ok(*x * 2)).map(|x| x + 2).collect(),
+        Ok(42)
+    );
+    assert!(matches!(
+        failed.map(|x| x + 1).collect(),
+        Err(WaitingError::DependencyFailed(_))
+    ));
+    assert!(matches!(
+        parent.map(|_| -> u64 { panic!("continuation") }).collect(),
+        Err(WaitingError::Panicked(_))
+    ));
+}
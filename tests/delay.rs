@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+use simboli_thread::{ClosureTask, ClosureThread, WaitingError};
+
+#[test]
+fn delayed_task_does_not_run_early() {
+    let thread_pool = ClosureThread::<Duration>::closures();
+    let start = Instant::now();
+    let delayed =
+        thread_pool.spawn_after_delay(Duration::from_millis(100), move || start.elapsed());
+    let at = thread_pool.spawn_at(start + Duration::from_millis(50), move || start.elapsed());
+    let past = thread_pool.spawn_at(start - Duration::from_millis(50), move || start.elapsed());
+
+    assert!(*delayed.block().unwrap() >= Duration::from_millis(100));
+    assert!(*at.block().unwrap() >= Duration::from_millis(50));
+    assert!(*past.block().unwrap() < Duration::from_millis(100));
+    thread_pool.join();
+}
+
+#[test]
+fn delayed_tasks_run_in_deadline_order() {
+    let thread_pool = ClosureThread::<Instant>::builder().threads(1).build();
+    let start = Instant::now();
+    let waitings: Vec<_> = (0..50u64)
+        .rev()
+        .map(|index| thread_pool.spawn_at(start + Duration::from_millis(index * 2), Instant::now))
+        .collect();
+
+    thread_pool.join();
+    let ran: Vec<Instant> = waitings
+        .iter()
+        .rev()
+        .map(|waiting| *waiting.get().unwrap())
+        .collect();
+    assert!(ran.windows(2).all(|pair| pair[0] <= pair[1]));
+}
+
+#[test]
+fn delayed_task_can_be_cancelled_and_depended_on() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let cancelled = thread_pool.spawn_after_delay(Duration::from_millis(50), || 1);
+    assert!(cancelled.cancel());
+    assert_eq!(cancelled.block(), Err(WaitingError::Cancelled));
+
+    let delayed = thread_pool.spawn_after_delay(Duration::from_millis(20), || 2);
+    let after = thread_pool.spawn_after(
+        &[&delayed],
+        ClosureTask::with_dependencies(|dependencies| dependencies[0].get().unwrap() + 1),
+    );
+    assert_eq!(after.collect(), Ok(3));
+    thread_pool.join();
+}
+
+#[test]
+fn join_waits_for_delayed_tasks() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let delayed = thread_pool.spawn_after_delay(Duration::from_millis(100), || 9);
+    thread_pool.join();
+    assert_eq!(delayed.get(), Some(&9));
+}
+
+#[test]
+fn join_does_not_wait_for_cancelled_delayed_tasks() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let start = Instant::now();
+    let cancelled = thread_pool.spawn_after_delay(Duration::from_secs(3), || 1);
+    assert!(cancelled.cancel());
+
+    thread_pool.join();
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(cancelled.block(), Err(WaitingError::Cancelled));
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use simboli_thread::{ClosureTask, ClosureThread, Waiting};
+
+#[test]
+fn empty_dependencies_are_already_done() {
+    let thread_pool = ClosureThread::<usize>::builder().threads(1).build();
+
+    let dependencies = thread_pool.spawn_task_dependencies_iter(Vec::<ClosureTask<usize>>::new());
+    assert!(dependencies.waiting_list.is_empty());
+    let dependent = thread_pool.spawn_with_dependencies(
+        |dependencies: &[Waiting<usize>]| dependencies.len() + 1,
+        &dependencies,
+    );
+    assert_eq!(dependent.block_timeout(Duration::from_secs(1)), Ok(&1));
+
+    let dependencies = thread_pool.spawn_dependencies(Vec::<fn() -> usize>::new());
+    assert!(dependencies.waiting_list.is_empty());
+    let dependent = thread_pool.spawn_task_with_dependencies(
+        ClosureTask::with_dependencies(|dependencies| dependencies.len() + 2),
+        &dependencies,
+    );
+    assert_eq!(dependent.block_timeout(Duration::from_secs(1)), Ok(&2));
+    thread_pool.join();
+}
+
+fn outputs(waitings: &[Waiting<u64>]) -> Vec<u64> {
+    waitings
+        .iter()
+        .map(|waiting| *waiting.get().unwrap())
+        .collect()
+}
+
+fn sum(dependencies: &[Waiting<u64>]) -> u64 {
+    outputs(dependencies).iter().sum()
+}
+
+#[test]
+fn pipeline_passes_outputs_through_each_level() {
+    let thread_pool = ClosureThread::<u64>::builder().threads(4).build();
+
+    // load, transform[k] scales the loaded total, reduce sums the transforms
+    let load = thread_pool.spawn_task_dependencies([
+        ClosureTask::new(|| 1),
+        ClosureTask::new(|| 2),
+        ClosureTask::new(|| 3),
+    ]);
+    let transform = thread_pool.spawn_task_dependencies_with_dependencies(
+        [
+            ClosureTask::with_dependencies(|dependencies| sum(dependencies) * 10),
+            ClosureTask::with_dependencies(|dependencies| sum(dependencies) * 20),
+        ],
+        &load,
+    );
+    let transform_iter = thread_pool.spawn_task_dependencies_with_dependencies_iter(
+        (1..=3u64)
+            .map(|k| ClosureTask::with_dependencies(move |dependencies| sum(dependencies) + k)),
+        &transform,
+    );
+    let reduce = thread_pool.spawn_with_dependencies(sum, &transform_iter);
+
+    assert_eq!(reduce.block(), Ok(&(3 * 180 + 6)));
+    assert_eq!(outputs(&load.waiting_list), vec![1, 2, 3]);
+    assert_eq!(outputs(&transform.waiting_list), vec![60, 120]);
+    assert_eq!(outputs(&transform_iter.waiting_list), vec![181, 182, 183]);
+    thread_pool.join();
+}
+
+#[test]
+fn closure_pipeline_passes_outputs_through_each_level() {
+    let thread_pool = ClosureThread::<u64>::builder().threads(4).build();
+
+    let load = thread_pool.spawn_dependencies((1..=4u64).map(|k| move || k * k));
+    let transform = thread_pool.spawn_dependencies_with_dependencies(
+        (0..2u64).map(|k| {
+            move |dependencies: &[Waiting<u64>]| outputs(dependencies)[k as usize * 2] + 100
+        }),
+        &load,
+    );
+    let reduce = thread_pool.spawn_with_dependencies(
+        |dependencies: &[Waiting<u64>]| outputs(dependencies).iter().product(),
+        &transform,
+    );
+
+    assert_eq!(reduce.block(), Ok(&(101 * 109)));
+    assert_eq!(outputs(&load.waiting_list), vec![1, 4, 9, 16]);
+    assert_eq!(outputs(&transform.waiting_list), vec![101, 109]);
+    thread_pool.join();
+}
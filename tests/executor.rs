@@ -0,0 +1,173 @@
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread::{self, sleep},
+    time::Duration,
+};
+
+use simboli_thread::{ClosureThread, WaitingError};
+
+#[derive(Default)]
+struct Pipe {
+    messages: VecDeque<u64>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+// in-process stand-in for a socket, a receiver is woken by the sender
+#[derive(Clone, Default)]
+struct Channel(Arc<Mutex<Pipe>>);
+
+impl Channel {
+    fn send(&self, message: u64) {
+        let mut pipe = self.0.lock().unwrap();
+        pipe.messages.push_back(message);
+        if let Some(waker) = pipe.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn close(&self) {
+        let mut pipe = self.0.lock().unwrap();
+        pipe.closed = true;
+        if let Some(waker) = pipe.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn recv(&self) -> Recv<'_> {
+        Recv(self)
+    }
+}
+
+struct Recv<'a>(&'a Channel);
+
+impl Future for Recv<'_> {
+    type Output = Option<u64>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<u64>> {
+        let mut pipe = self.0.0.lock().unwrap();
+        if let Some(message) = pipe.messages.pop_front() {
+            return Poll::Ready(Some(message));
+        }
+        if pipe.closed {
+            return Poll::Ready(None);
+        }
+        pipe.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+// pending once, woken right away
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[test]
+fn echo_servers_and_clients_run_as_futures() {
+    for threads in [1, 4] {
+        let thread_pool = ClosureThread::<u64>::builder().threads(threads).build();
+        let mut connections = Vec::new();
+        for client in 0..20u64 {
+            let (requests, responses) = (Channel::default(), Channel::default());
+
+            let (inbox, outbox) = (requests.clone(), responses.clone());
+            let server = thread_pool.spawn_future(async move {
+                let mut echoed = 0;
+                while let Some(message) = inbox.recv().await {
+                    outbox.send(message);
+                    echoed += 1;
+                    YieldNow(false).await;
+                }
+                outbox.close();
+                echoed
+            });
+
+            let client = thread_pool.spawn_future(async move {
+                let mut total = 0;
+                for index in 0..50 {
+                    requests.send(client * 1_000 + index);
+                    total += responses.recv().await.unwrap();
+                }
+                requests.close();
+                while responses.recv().await.is_some() {}
+                total
+            });
+            connections.push((client, server));
+        }
+
+        for (index, (client, server)) in connections.into_iter().enumerate() {
+            let expected: u64 = (0..50).map(|message| index as u64 * 1_000 + message).sum();
+            assert_eq!(client.collect(), Ok(expected));
+            assert_eq!(server.collect(), Ok(50));
+        }
+        thread_pool.join();
+    }
+}
+
+#[test]
+fn spawned_future_awaits_pool_tasks() {
+    let thread_pool = Arc::new(ClosureThread::<u64>::closures());
+    let inner = thread_pool.clone();
+    let waiting = thread_pool.spawn_future(async move {
+        let mut total = 0;
+        for index in 0..100 {
+            total += inner.spawn(move || index).await.unwrap();
+        }
+        total
+    });
+    assert_eq!(waiting.collect(), Ok(4_950));
+}
+
+#[test]
+fn panicking_future_is_panicked() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let waiting = thread_pool.spawn_future(async {
+        YieldNow(false).await;
+        panic!("future panic")
+    });
+    assert!(matches!(waiting.collect(), Err(WaitingError::Panicked(_))));
+    thread_pool.join();
+}
+
+#[test]
+fn join_waits_for_a_future_woken_from_outside() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let channel = Channel::default();
+    let receiver = channel.clone();
+    let waiting = thread_pool.spawn_future(async move { receiver.recv().await.unwrap() });
+    thread::spawn(move || {
+        sleep(Duration::from_millis(30));
+        channel.send(9);
+    });
+    thread_pool.join();
+    assert_eq!(waiting.collect(), Ok(9));
+}
+
+#[test]
+fn future_cancelled_before_its_first_poll_is_not_polled() {
+    let thread_pool = ClosureThread::<u64>::builder().threads(1).build();
+    let busy = thread_pool.spawn(|| {
+        sleep(Duration::from_millis(30));
+        0
+    });
+    let waiting = thread_pool.spawn_future(async { panic!("polled after cancel") });
+    assert!(waiting.cancel());
+    assert_eq!(waiting.collect(), Err(WaitingError::Cancelled));
+    busy.collect().unwrap();
+    thread_pool.join();
+}
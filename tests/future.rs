@@ -0,0 +1,105 @@
+use std::{
+    future::{Future, IntoFuture},
+    pin::{Pin, pin},
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread, sleep},
+    time::Duration,
+};
+
+use simboli_thread::{AnyThread, ClosureThread, WaitingError};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// minimal executor, the current thread parks until the future is woken
+fn block_on<T: Future>(future: T) -> T::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+#[test]
+fn await_waiting_outputs() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let output = block_on(async {
+        let slow = thread_pool.spawn(|| {
+            sleep(Duration::from_millis(20));
+            1
+        });
+        let fast = thread_pool.spawn(|| 2);
+        slow.await.unwrap() + fast.await.unwrap()
+    });
+    assert_eq!(output, 3);
+
+    let total = block_on(async {
+        let waitings: Vec<_> = (0..1_000)
+            .map(|index| thread_pool.spawn(move || index))
+            .collect();
+        let mut total = 0;
+        for waiting in waitings {
+            total += waiting.await.unwrap();
+        }
+        total
+    });
+    assert_eq!(total, 499_500);
+    thread_pool.join();
+}
+
+#[test]
+fn await_failed_tasks() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let panicked = block_on(thread_pool.spawn(|| panic!("awaited panic")).into_future());
+    assert!(matches!(panicked, Err(WaitingError::Panicked(_))));
+
+    let cancelled = thread_pool.spawn_after_delay(Duration::from_millis(50), || 1);
+    assert!(cancelled.cancel());
+    assert_eq!(
+        block_on(cancelled.into_future()),
+        Err(WaitingError::Cancelled)
+    );
+    thread_pool.join();
+}
+
+#[test]
+fn poll_with_a_new_waker_each_time() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let mut future = thread_pool
+        .spawn(|| {
+            sleep(Duration::from_millis(10));
+            5
+        })
+        .into_future();
+    let output = loop {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        match Pin::new(&mut future).poll(&mut Context::from_waker(&waker)) {
+            Poll::Ready(output) => break output,
+            Poll::Pending => thread::park_timeout(Duration::from_millis(5)),
+        }
+    };
+    assert_eq!(output, Ok(5));
+    thread_pool.join();
+}
+
+#[test]
+fn await_typed_outputs() {
+    let thread_pool = AnyThread::closures();
+    let text = block_on(
+        thread_pool
+            .spawn_typed(|| "typed".to_string())
+            .into_future(),
+    );
+    assert_eq!(text.unwrap(), "typed");
+    thread_pool.join();
+}
@@ -0,0 +1,212 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread::yield_now,
+    time::Duration,
+};
+
+use simboli_thread::{
+    ClosureTask, ClosureThread, GraphSnapshot, SnapshotTask, TaskGraph, TaskGraphError, TaskState,
+    Waiting,
+};
+
+type Graph = TaskGraph<ClosureTask<u64>, ClosureTask<u64>, u64>;
+
+fn sum(dependencies: &[Waiting<u64>]) -> u64 {
+    dependencies
+        .iter()
+        .map(|waiting| *waiting.get().unwrap())
+        .sum()
+}
+
+#[test]
+fn diamond_runs_in_dependency_order() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let external = thread_pool.spawn(|| 100);
+
+    let mut graph = Graph::new();
+    let a = graph.add_task(ClosureTask::new(|| 10));
+    let b = graph.add_task_with_dependencies(ClosureTask::with_dependencies(|d| sum(d) + 1));
+    let c = graph.add_task_with_dependencies(ClosureTask::with_dependencies(|d| sum(d) * 2));
+    let d = graph.add_task_with_dependencies(ClosureTask::with_dependencies(sum));
+    graph.depends_on(b, a).depends_on_waiting(b, &external);
+    graph.depends_on(c, a);
+    graph.depends_on(d, b).depends_on(d, c);
+
+    let waitings = thread_pool.spawn_graph(graph).unwrap();
+    assert_eq!(
+        waitings[d.index()].block_timeout(Duration::from_secs(10)),
+        Ok(&(111 + 20))
+    );
+    thread_pool.join();
+}
+
+#[test]
+fn cycle_is_rejected_and_nothing_is_spawned() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let mut graph = Graph::new();
+    let counter = runs.clone();
+    let start = graph.add_task(ClosureTask::new(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        1
+    }));
+    let x = graph.add_task_with_dependencies(ClosureTask::with_dependencies(sum));
+    let y = graph.add_task_with_dependencies(ClosureTask::with_dependencies(sum));
+    let z = graph.add_task_with_dependencies(ClosureTask::with_dependencies(sum));
+    // x -> y -> z -> x, through other nodes
+    graph
+        .depends_on(x, start)
+        .depends_on(y, x)
+        .depends_on(z, y)
+        .depends_on(x, z);
+
+    assert!(matches!(
+        thread_pool.spawn_graph(graph),
+        Err(TaskGraphError::Cycle(_))
+    ));
+    thread_pool.join();
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn self_dependency_is_a_cycle() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let mut graph = Graph::new();
+    let node = graph.add_task_with_dependencies(ClosureTask::with_dependencies(sum));
+    graph.depends_on(node, node);
+
+    assert_eq!(
+        thread_pool.spawn_graph(graph).err(),
+        Some(TaskGraphError::Cycle(node))
+    );
+    thread_pool.join();
+}
+
+#[test]
+fn node_of_another_graph_is_unknown() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let mut other = Graph::new();
+    other.add_task(ClosureTask::new(|| 1));
+    let foreign = other.add_task(ClosureTask::new(|| 2));
+
+    let mut graph = Graph::new();
+    let node = graph.add_task_with_dependencies(ClosureTask::with_dependencies(sum));
+    graph.depends_on(node, foreign);
+
+    assert!(matches!(
+        thread_pool.spawn_graph(graph),
+        Err(TaskGraphError::UnknownNode(_))
+    ));
+    thread_pool.join();
+}
+
+#[test]
+fn empty_graph_spawns_nothing() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    assert!(thread_pool.spawn_graph(Graph::new()).unwrap().is_empty());
+    thread_pool.join();
+}
+
+fn snapshot_task<'a>(snapshot: &'a GraphSnapshot, waiting: &Waiting<u64>) -> &'a SnapshotTask {
+    snapshot
+        .tasks
+        .iter()
+        .find(|task| task.id == waiting.id())
+        .unwrap()
+}
+
+#[test]
+fn snapshot_shows_task_states_and_spawn_after_edges() {
+    let thread_pool = ClosureThread::<u64>::builder()
+        .threads(1)
+        .graph_tracing(true)
+        .build();
+    let done = thread_pool.spawn(|| 1);
+    done.block().unwrap();
+
+    // the only worker runs the gate, everything after it stays pending
+    let started = Arc::new(AtomicBool::new(false));
+    let open = Arc::new(AtomicBool::new(false));
+    let (gate_started, gate_open) = (started.clone(), open.clone());
+    let gate = thread_pool.spawn(move || {
+        gate_started.store(true, Ordering::SeqCst);
+        while !gate_open.load(Ordering::SeqCst) {
+            yield_now();
+        }
+        2
+    });
+    while !started.load(Ordering::SeqCst) {
+        yield_now();
+    }
+
+    let queued = thread_pool.spawn(|| 3);
+    let first = thread_pool.spawn_after(&[&done, &gate], ClosureTask::with_dependencies(sum));
+    let second = thread_pool.spawn_after(&[&first], ClosureTask::with_dependencies(sum));
+
+    let snapshot = thread_pool.graph_snapshot().unwrap();
+    assert_eq!(snapshot_task(&snapshot, &done).state, TaskState::Done);
+    assert_eq!(snapshot_task(&snapshot, &gate).state, TaskState::Running);
+    assert_eq!(snapshot_task(&snapshot, &queued).state, TaskState::Queued);
+    assert_eq!(snapshot_task(&snapshot, &first).state, TaskState::Waiting);
+    assert_eq!(snapshot_task(&snapshot, &second).state, TaskState::Waiting);
+
+    let mut dependencies = snapshot_task(&snapshot, &first).dependencies.clone();
+    dependencies.sort();
+    assert_eq!(dependencies, vec![done.id(), gate.id()]);
+    assert_eq!(
+        snapshot_task(&snapshot, &second).dependencies,
+        vec![first.id()]
+    );
+
+    let dot = snapshot.to_dot();
+    assert!(dot.contains(&format!("t{} -> t{};", done.id(), first.id())));
+    assert!(dot.contains(&format!("t{} -> t{};", gate.id(), first.id())));
+    assert!(dot.contains(&format!("t{} -> t{};", first.id(), second.id())));
+
+    open.store(true, Ordering::SeqCst);
+    assert_eq!(second.block(), Ok(&3));
+    thread_pool.join();
+}
+
+#[test]
+fn snapshot_labels_are_escaped_in_dot() {
+    let thread_pool = ClosureThread::<u64>::builder()
+        .threads(1)
+        .graph_tracing(true)
+        .build();
+    let open = Arc::new(AtomicBool::new(false));
+    let gate_open = open.clone();
+    let gate = thread_pool.spawn(move || {
+        while !gate_open.load(Ordering::SeqCst) {
+            yield_now();
+        }
+        0
+    });
+    gate.set_label("say \"hi\"\\path\nnext");
+
+    let snapshot = thread_pool.graph_snapshot().unwrap();
+    assert_eq!(
+        snapshot_task(&snapshot, &gate).label.as_deref(),
+        Some("say \"hi\"\\path\nnext")
+    );
+    assert!(snapshot.to_dot().contains(&format!(
+        "label=\"{} say \\\"hi\\\"\\\\path\\nnext\\n",
+        gate.id()
+    )));
+
+    open.store(true, Ordering::SeqCst);
+    thread_pool.join();
+}
+
+#[test]
+fn snapshot_is_none_without_graph_tracing() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let waiting = thread_pool.spawn(|| 1);
+    assert!(thread_pool.graph_snapshot().is_none());
+    assert_eq!(waiting.block(), Ok(&1));
+    thread_pool.join();
+}
@@ -0,0 +1,63 @@
+use std::{
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use simboli_thread::{ClosureTask, ClosureThread, IdleStrategy, Waiting};
+
+// parked workers only wake up on their own after 10s
+fn parked_pool() -> ClosureThread<u64> {
+    let thread_pool = ClosureThread::<u64>::builder()
+        .threads(4)
+        .idle_strategy(IdleStrategy::new(16, 4, Duration::from_secs(10)))
+        .build();
+    // past the spin and yield rounds, every worker is parked
+    sleep(Duration::from_millis(200));
+    thread_pool
+}
+
+#[test]
+fn parked_workers_wake_up_for_dependency_released_tasks() {
+    let thread_pool = parked_pool();
+    let start = Instant::now();
+    let dependencies = thread_pool.spawn_dependencies([|| 1, || 2]);
+    let dependent = thread_pool.spawn_with_dependencies(
+        |dependencies: &[Waiting<u64>]| {
+            dependencies
+                .iter()
+                .map(|waiting| *waiting.get().unwrap())
+                .sum()
+        },
+        &dependencies,
+    );
+    assert_eq!(dependent.block(), Ok(&3));
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    // released at once by a parent done before the workers parked
+    sleep(Duration::from_millis(200));
+    let start = Instant::now();
+    let after = thread_pool.spawn_after(
+        &[&dependent],
+        ClosureTask::with_dependencies(|dependencies| dependencies[0].get().unwrap() + 1),
+    );
+    assert_eq!(after.block(), Ok(&4));
+    assert!(start.elapsed() < Duration::from_secs(2));
+    thread_pool.join();
+}
+
+#[test]
+fn parked_workers_wake_up_for_timer_released_tasks() {
+    let thread_pool = parked_pool();
+    let start = Instant::now();
+    let delayed = thread_pool.spawn_after_delay(Duration::from_millis(100), || 5);
+    let after = thread_pool.spawn_after(
+        &[&delayed],
+        ClosureTask::with_dependencies(|dependencies| dependencies[0].get().unwrap() * 2),
+    );
+
+    assert_eq!(after.block(), Ok(&10));
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(100));
+    assert!(elapsed < Duration::from_secs(2));
+    thread_pool.join();
+}
@@ -0,0 +1,84 @@
+use simboli_thread::{ClosureTask, ClosureThread, WaitingError, current, join};
+
+fn fib(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    let (a, b) = join(|| fib(n - 1), || fib(n - 2));
+    a.unwrap() + b.unwrap()
+}
+
+// borrows the slice, no Arc needed
+fn sum(items: &[u64]) -> u64 {
+    if items.len() <= 64 {
+        return items.iter().sum();
+    }
+    let (left, right) = items.split_at(items.len() / 2);
+    let (a, b) = join(|| sum(left), || sum(right));
+    a.unwrap() + b.unwrap()
+}
+
+#[test]
+fn join_splits_recursively_on_the_workers() {
+    for threads in [1, 4] {
+        let thread_pool = ClosureThread::<u64>::builder()
+            .threads(threads)
+            .queue_capacity(8)
+            .build();
+        assert_eq!(thread_pool.spawn(|| fib(18)).collect(), Ok(2584));
+        assert_eq!(
+            thread_pool
+                .spawn(|| {
+                    let items: Vec<u64> = (0..10_000).collect();
+                    sum(&items)
+                })
+                .collect(),
+            Ok((0..10_000).sum())
+        );
+        thread_pool.join();
+    }
+}
+
+#[test]
+fn join_reports_a_panic_as_panicked() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let waiting = thread_pool.spawn(|| {
+        let local = [1, 2, 3];
+        let (a, b) = join(|| local.iter().sum::<u64>(), || -> u64 { panic!("inner") });
+        match b {
+            Err(WaitingError::Panicked(task_panic)) => assert_eq!(task_panic.message(), "inner"),
+            _ => panic!("panic expected"),
+        }
+        a.unwrap()
+    });
+    assert_eq!(waiting.collect(), Ok(6));
+    thread_pool.join();
+}
+
+#[test]
+fn join_outside_a_worker_runs_both_closures() {
+    assert!(current::<ClosureTask<u64>, ClosureTask<u64>, u64>().is_none());
+    let local = vec![4u64, 5];
+    let (a, b) = join(|| local[0] * 2, || -> u64 { panic!("outside") });
+    assert_eq!(a, Ok(8));
+    assert!(matches!(b, Err(WaitingError::Panicked(_))));
+    assert_eq!(sum(&local), 9);
+}
+
+#[test]
+fn worker_spawns_and_waits_without_blocking_the_pool() {
+    let thread_pool = ClosureThread::<u64>::builder().threads(1).build();
+    let waiting = thread_pool.spawn(|| {
+        let worker = current::<ClosureTask<u64>, ClosureTask<u64>, u64>().unwrap();
+        let waitings: Vec<_> = (0..100).map(|index| worker.spawn(move || index)).collect();
+        waitings
+            .iter()
+            .map(|waiting| {
+                worker.wait(waiting);
+                *waiting.get().unwrap()
+            })
+            .sum()
+    });
+    assert_eq!(waiting.collect(), Ok(4950));
+    thread_pool.join();
+}
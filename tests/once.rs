@@ -0,0 +1,56 @@
+use simboli_thread::{SimboliThread, TaskOnceTrait, TaskWithDependenciesOnceTrait, Waiting};
+
+// a task owning its data, the data is moved into the output instead of cloned
+enum BatchTask {
+    Load(Vec<u64>),
+    Merge(Vec<u64>),
+}
+
+impl TaskOnceTrait<Vec<u64>> for BatchTask {
+    fn exec_once(self) -> Vec<u64> {
+        match self {
+            BatchTask::Load(batch) => batch,
+            BatchTask::Merge(_) => Vec::new(),
+        }
+    }
+}
+
+impl TaskWithDependenciesOnceTrait<Vec<u64>> for BatchTask {
+    fn exec_once(self, dependencies: &[Waiting<Vec<u64>>]) -> Vec<u64> {
+        match self {
+            BatchTask::Load(batch) => batch,
+            BatchTask::Merge(mut batch) => {
+                for dependency in dependencies {
+                    batch.extend_from_slice(dependency.get().unwrap());
+                }
+                batch
+            }
+        }
+    }
+}
+
+#[test]
+fn once_tasks_move_their_data_into_the_output() {
+    let thread_pool = SimboliThread::<BatchTask, BatchTask, Vec<u64>>::builder()
+        .threads(2)
+        .build();
+
+    let batch = vec![1, 2, 3];
+    let batch_ptr = batch.as_ptr();
+    let loaded = thread_pool.spawn_task(BatchTask::Load(batch));
+    assert_eq!(loaded.block().unwrap(), &[1, 2, 3]);
+    // the same allocation, nothing was cloned on the way
+    assert_eq!(loaded.get().unwrap().as_ptr(), batch_ptr);
+
+    let dependencies = thread_pool
+        .spawn_task_dependencies_iter([BatchTask::Load(vec![4, 5]), BatchTask::Load(vec![6])]);
+    let merge = Vec::with_capacity(8);
+    let merge_ptr = merge.as_ptr();
+    let merged = thread_pool.spawn_task_with_dependencies(BatchTask::Merge(merge), &dependencies);
+
+    let merged = merged.collect().unwrap();
+    assert_eq!(merged.len(), 3);
+    assert_eq!(merged.iter().sum::<u64>(), 15);
+    assert_eq!(merged.as_ptr(), merge_ptr);
+    thread_pool.join();
+}
@@ -0,0 +1,48 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread::yield_now,
+};
+
+use simboli_thread::ClosureThread;
+
+// counts its drops
+struct Tracked(Arc<AtomicUsize>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn output_is_freed_once_the_waiting_and_the_task_are_gone() {
+    let thread_pool = ClosureThread::<Tracked>::builder().threads(1).build();
+    let drops = Arc::new(AtomicUsize::new(0));
+
+    let counter = drops.clone();
+    let kept = thread_pool.spawn(move || Tracked(counter));
+    kept.block().ok();
+
+    // the Waiting is dropped before the task has stored its output
+    let open = Arc::new(AtomicBool::new(false));
+    let (gate_open, counter) = (open.clone(), drops.clone());
+    let dropped = thread_pool.spawn(move || {
+        while !gate_open.load(Ordering::SeqCst) {
+            yield_now();
+        }
+        Tracked(counter)
+    });
+    drop(dropped);
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    // every task is gone, only the output still held by a Waiting is left
+    open.store(true, Ordering::SeqCst);
+    thread_pool.join();
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+
+    drop(kept);
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+}
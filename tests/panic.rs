@@ -0,0 +1,111 @@
+use simboli_thread::{
+    ArrTaskDependenciesTrait, SimboliThread, TaskTrait, TaskWithDependenciesTrait, Waiting,
+    WaitingError,
+};
+
+struct Output(i32);
+
+enum Task {
+    Value(i32),
+    Panic,
+    Sum,
+}
+
+impl TaskTrait<Output> for Task {
+    fn exec(&self) -> Output {
+        match self {
+            Task::Value(value) => Output(*value),
+            Task::Panic => panic!("task panic"),
+            Task::Sum => Output(0),
+        }
+    }
+}
+
+impl TaskWithDependenciesTrait<Output> for Task {
+    fn exec(&self, dependencies: &[Waiting<Output>]) -> Output {
+        Output(
+            dependencies
+                .iter()
+                .map(|waiting| waiting.get().unwrap().0)
+                .sum(),
+        )
+    }
+}
+
+impl ArrTaskDependenciesTrait<Task, Output, 2> for [Task; 2] {
+    fn task_list(self) -> [Task; 2] {
+        self
+    }
+}
+
+fn thread_pool() -> SimboliThread<Task, Task, Output> {
+    SimboliThread::builder()
+        .threads(4)
+        .queue_capacity(64)
+        .build()
+}
+
+#[test]
+fn panicking_task_does_not_stop_the_workers() {
+    let thread_pool = thread_pool();
+    let waitings: Vec<Waiting<Output>> = (0..500)
+        .map(|index| {
+            if index % 5 == 0 {
+                thread_pool.spawn_task(Task::Panic)
+            } else {
+                thread_pool.spawn_task(Task::Value(1))
+            }
+        })
+        .collect();
+
+    let (mut ok, mut panicked) = (0, 0);
+    for waiting in waitings {
+        match waiting.collect() {
+            Ok(output) => ok += output.0,
+            Err(WaitingError::Panicked(task_panic)) => {
+                assert_eq!(task_panic.message(), "task panic");
+                panicked += 1;
+            }
+            Err(error) => panic!("unexpected error {}", error),
+        }
+    }
+    assert_eq!((ok, panicked), (400, 100));
+
+    // the workers are still alive
+    assert_eq!(
+        thread_pool.spawn_task(Task::Value(5)).collect().unwrap().0,
+        5
+    );
+    thread_pool.join();
+}
+
+#[test]
+fn panicking_dependency_fails_the_dependent_task() {
+    let thread_pool = thread_pool();
+    let dependencies = thread_pool.spawn_task_dependencies([Task::Value(1), Task::Panic]);
+    let waiting = thread_pool.spawn_task_with_dependencies(Task::Sum, &dependencies);
+
+    match waiting.block() {
+        Err(WaitingError::DependencyFailed(task_panic)) => {
+            assert_eq!(task_panic.message(), "task panic")
+        }
+        _ => panic!("dependency failure expected"),
+    }
+
+    let dependencies = thread_pool.spawn_task_dependencies([Task::Value(1), Task::Value(2)]);
+    let waiting = thread_pool.spawn_task_with_dependencies(Task::Sum, &dependencies);
+    assert_eq!(waiting.block().unwrap().0, 3);
+    thread_pool.join();
+}
+
+#[test]
+fn panic_id_is_the_task_id() {
+    let thread_pool = thread_pool();
+    let waiting = thread_pool.spawn_task(Task::Panic);
+    let id = waiting.id();
+    match waiting.collect() {
+        Err(WaitingError::Panicked(task_panic)) => assert_eq!(task_panic.id(), id),
+        _ => panic!("panic expected"),
+    }
+    thread_pool.join();
+}
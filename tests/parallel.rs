@@ -0,0 +1,108 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use simboli_thread::ClosureThread;
+
+// output type of the thread pool without Default
+struct Report(#[allow(dead_code)] String);
+
+#[test]
+fn par_outputs_keep_the_item_order() {
+    for threads in [1, 4] {
+        let thread_pool = ClosureThread::<u64>::builder().threads(threads).build();
+        let items: Vec<u64> = (0..10_000).collect();
+
+        let squares = thread_pool.par_map(&items, |item| item * item);
+        assert_eq!(
+            squares,
+            items.iter().map(|item| item * item).collect::<Vec<_>>()
+        );
+
+        let words: Vec<String> = (0..300).map(|index| index.to_string()).collect();
+        let joined = thread_pool
+            .par_iter(&words)
+            .task_size(7)
+            .map(String::clone)
+            .reduce(String::new, |a, b| a + &b);
+        assert_eq!(joined, words.concat());
+
+        let total = AtomicU64::new(0);
+        thread_pool.par_for_each(&items, |item| {
+            total.fetch_add(*item, Ordering::Relaxed);
+        });
+        assert_eq!(total.load(Ordering::Relaxed), (0..10_000).sum());
+        thread_pool.join();
+    }
+}
+
+#[test]
+fn par_chunks_last_chunk_is_the_remainder() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let items: Vec<u64> = (0..10).collect();
+    let lengths = thread_pool
+        .par_chunks(&items, 3)
+        .map(<[u64]>::len)
+        .collect();
+    assert_eq!(lengths, vec![3, 3, 3, 1]);
+    assert!(
+        thread_pool
+            .par_map(&Vec::<u64>::new(), |item| *item)
+            .is_empty()
+    );
+    assert_eq!(
+        thread_pool.par_reduce(&Vec::<u64>::new(), || 5, |a, b| a + b),
+        5
+    );
+    thread_pool.join();
+}
+
+#[test]
+fn par_works_without_a_default_output() {
+    let thread_pool = ClosureThread::<Report>::closures();
+    let items: Vec<u64> = (0..1_000).collect();
+    assert_eq!(
+        thread_pool.par_reduce(&items, || 0, |a, b| a + b),
+        (0..1_000).sum()
+    );
+    assert!(
+        thread_pool
+            .spawn(|| Report("done".to_string()))
+            .collect()
+            .is_ok()
+    );
+    thread_pool.join();
+}
+
+#[test]
+fn par_panic_is_raised_in_the_caller() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let items: Vec<u64> = (0..1_000).collect();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        thread_pool.par_for_each(&items, |item| {
+            if *item == 777 {
+                panic!("item panic");
+            }
+        })
+    }));
+    assert!(result.is_err());
+
+    // the thread pool keeps working
+    assert_eq!(
+        thread_pool.par_map(&items[..3], |item| item + 1),
+        vec![1, 2, 3]
+    );
+    thread_pool.join();
+}
+
+#[test]
+fn par_inside_a_task() {
+    let thread_pool = std::sync::Arc::new(ClosureThread::<u64>::builder().threads(2).build());
+    let inner = thread_pool.clone();
+    let waiting = thread_pool.spawn(move || {
+        let items: Vec<u64> = (0..100).collect();
+        inner.par_map(&items, |item| item * 2).iter().sum()
+    });
+    assert_eq!(waiting.collect(), Ok(9_900));
+}
@@ -0,0 +1,119 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use simboli_thread::{ClosureThread, PeriodicMode};
+
+#[test]
+fn stop_ends_the_runs() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let runs = Arc::new(AtomicU64::new(0));
+    let counter = runs.clone();
+    let handle = thread_pool.spawn_periodic(Duration::from_millis(5), move || {
+        counter.fetch_add(1, Ordering::SeqCst)
+    });
+
+    while handle.runs() < 3 {
+        sleep(Duration::from_millis(1));
+    }
+    handle.stop();
+    assert!(handle.is_stopped());
+
+    // a run started before stop may still finish
+    sleep(Duration::from_millis(20));
+    let at_stop = runs.load(Ordering::SeqCst);
+    sleep(Duration::from_millis(50));
+    assert_eq!(runs.load(Ordering::SeqCst), at_stop);
+    assert_eq!(handle.runs(), at_stop);
+    thread_pool.join();
+}
+
+#[test]
+fn join_stops_periodic_tasks() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let handle = thread_pool.spawn_periodic(Duration::from_secs(3600), || 1);
+    let start = Instant::now();
+    thread_pool.join();
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert!(handle.is_stopped());
+    assert_eq!(handle.runs(), 0);
+}
+
+#[test]
+fn runs_never_overlap() {
+    let thread_pool = ClosureThread::<u64>::builder().threads(4).build();
+    let starts = Arc::new(Mutex::new(Vec::new()));
+    let ends = Arc::new(Mutex::new(Vec::new()));
+    let (run_starts, run_ends) = (starts.clone(), ends.clone());
+    let handle = thread_pool.spawn_periodic_with_mode(
+        Duration::from_millis(2),
+        PeriodicMode::FixedRate { skip_missed: false },
+        move || {
+            run_starts.lock().unwrap().push(Instant::now());
+            sleep(Duration::from_millis(5));
+            run_ends.lock().unwrap().push(Instant::now());
+            0
+        },
+    );
+
+    while handle.runs() < 5 {
+        sleep(Duration::from_millis(1));
+    }
+    handle.stop();
+    thread_pool.join();
+
+    let (starts, ends) = (starts.lock().unwrap(), ends.lock().unwrap());
+    assert_eq!(starts.len(), ends.len());
+    assert!(
+        starts
+            .iter()
+            .skip(1)
+            .zip(ends.iter())
+            .all(|(start, end)| start >= end)
+    );
+}
+
+#[test]
+fn fixed_delay_waits_after_each_run() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let starts = Arc::new(Mutex::new(Vec::new()));
+    let run_starts = starts.clone();
+    let handle = thread_pool.spawn_periodic_with_mode(
+        Duration::from_millis(10),
+        PeriodicMode::FixedDelay,
+        move || {
+            run_starts.lock().unwrap().push(Instant::now());
+            sleep(Duration::from_millis(10));
+            0
+        },
+    );
+
+    while handle.runs() < 4 {
+        sleep(Duration::from_millis(1));
+    }
+    handle.stop();
+    thread_pool.join();
+
+    let starts = starts.lock().unwrap();
+    assert!(
+        starts
+            .windows(2)
+            .all(|pair| pair[1] - pair[0] >= Duration::from_millis(20))
+    );
+}
+
+#[test]
+fn panicking_run_does_not_stop_the_task() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let handle = thread_pool.spawn_periodic(Duration::from_millis(5), || panic!("periodic panic"));
+    while handle.runs() < 3 {
+        sleep(Duration::from_millis(1));
+    }
+    handle.stop();
+    thread_pool.join();
+}
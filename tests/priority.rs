@@ -0,0 +1,112 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{sleep, yield_now},
+    time::Duration,
+};
+
+use simboli_thread::{ClosureTask, ClosureThread, Priority, TaskGraph};
+
+const LOW_TASKS: u64 = 200;
+const HIGH: u64 = 1_000;
+
+// position of the high priority task among the runs, a few low tasks may be queued already
+fn high_position(order: &Mutex<Vec<u64>>) -> usize {
+    let order = order.lock().unwrap();
+    assert_eq!(order.len() as u64, LOW_TASKS + 1);
+    order.iter().position(|&task| task == HIGH).unwrap()
+}
+
+#[test]
+fn high_priority_overtakes_queued_low_tasks() {
+    let thread_pool = ClosureThread::<u64>::builder().threads(1).build();
+    let gate = Arc::new(AtomicBool::new(false));
+    let order = Arc::new(Mutex::new(Vec::new()));
+
+    let is_open = gate.clone();
+    let busy = thread_pool.spawn(move || {
+        while !is_open.load(Ordering::SeqCst) {
+            yield_now();
+        }
+        0
+    });
+    for task in 0..LOW_TASKS {
+        let order = order.clone();
+        thread_pool.spawn_with_priority(
+            move || {
+                order.lock().unwrap().push(task);
+                task
+            },
+            Priority::Low,
+        );
+    }
+    let high_order = order.clone();
+    let high = thread_pool.spawn_with_priority(
+        move || {
+            high_order.lock().unwrap().push(HIGH);
+            HIGH
+        },
+        Priority::High,
+    );
+
+    gate.store(true, Ordering::SeqCst);
+    busy.collect().unwrap();
+    high.collect().unwrap();
+    thread_pool.join();
+    assert!(high_position(&order) < 10);
+}
+
+#[test]
+fn released_dependents_keep_their_priority() {
+    let thread_pool = ClosureThread::<u64>::builder().threads(1).build();
+    let dependencies = thread_pool.spawn_dependencies([|| {
+        sleep(Duration::from_millis(20));
+        1
+    }]);
+    let order = Arc::new(Mutex::new(Vec::new()));
+
+    for task in 0..LOW_TASKS {
+        let order = order.clone();
+        thread_pool.spawn_with_dependencies_with_priority(
+            move |_| {
+                order.lock().unwrap().push(task);
+                task
+            },
+            &dependencies,
+            Priority::Low,
+        );
+    }
+    let high_order = order.clone();
+    thread_pool.spawn_with_dependencies_with_priority(
+        move |_| {
+            high_order.lock().unwrap().push(HIGH);
+            HIGH
+        },
+        &dependencies,
+        Priority::High,
+    );
+
+    thread_pool.join();
+    assert!(high_position(&order) < 10);
+}
+
+#[test]
+fn priority_after_dependencies_and_in_graphs() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let first = thread_pool.spawn_with_priority(|| 2, Priority::High);
+    let second = thread_pool.spawn_after_with_priority(
+        &[&first],
+        ClosureTask::with_dependencies(|dependencies| dependencies[0].get().unwrap() * 10),
+        Priority::High,
+    );
+    assert_eq!(second.collect(), Ok(20));
+
+    let mut graph = TaskGraph::new();
+    let node = graph.add_task(ClosureTask::new(|| 3));
+    graph.set_priority(node, Priority::Low);
+    let waitings = thread_pool.spawn_graph(graph).unwrap();
+    assert_eq!(waitings[node.index()].block(), Ok(&3));
+    thread_pool.join();
+}
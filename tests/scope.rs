@@ -0,0 +1,113 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread::sleep,
+    time::Duration,
+};
+
+use simboli_thread::{ClosureThread, WaitingError};
+
+#[test]
+fn scoped_tasks_borrow_local_data() {
+    let thread_pool = ClosureThread::<u64>::builder().threads(4).build();
+    let items: Vec<u64> = (0..10_000).collect();
+    let total = AtomicU64::new(0);
+    let mut buffer = vec![0u64; 100];
+
+    thread_pool.scope(|s| {
+        for chunk in items.chunks(1_000) {
+            let total = &total;
+            s.spawn(move || {
+                total.fetch_add(chunk.iter().sum(), Ordering::Relaxed);
+                0
+            });
+        }
+        for (index, slots) in buffer.chunks_mut(10).enumerate() {
+            s.spawn(move || {
+                slots.fill(index as u64);
+                0
+            });
+        }
+    });
+
+    assert_eq!(total.load(Ordering::Relaxed), (0..10_000).sum());
+    assert!(
+        buffer
+            .iter()
+            .enumerate()
+            .all(|(index, slot)| *slot == (index / 10) as u64)
+    );
+    thread_pool.join();
+}
+
+#[test]
+fn scope_waits_for_nested_and_slow_tasks() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let done = AtomicU64::new(0);
+    thread_pool.scope(|s| {
+        s.spawn(|| {
+            s.spawn(|| {
+                sleep(Duration::from_millis(30));
+                done.fetch_add(1, Ordering::SeqCst);
+                0
+            });
+            0
+        });
+    });
+    assert_eq!(done.load(Ordering::SeqCst), 1);
+    thread_pool.join();
+}
+
+#[test]
+fn scoped_panic_is_returned_by_its_waiting() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let output = thread_pool.scope(|s| {
+        let waiting = s.spawn(|| panic!("scoped panic"));
+        matches!(waiting.block(), Err(WaitingError::Panicked(_)))
+    });
+    assert!(output);
+    thread_pool.join();
+}
+
+#[test]
+fn panic_in_the_scope_body_waits_for_the_tasks() {
+    let thread_pool = ClosureThread::<u64>::closures();
+    let done = AtomicU64::new(0);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        thread_pool.scope(|s| {
+            s.spawn(|| {
+                sleep(Duration::from_millis(30));
+                done.fetch_add(1, Ordering::SeqCst);
+                0
+            });
+            panic!("scope body");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(done.load(Ordering::SeqCst), 1);
+    thread_pool.join();
+}
+
+#[test]
+fn scope_on_a_worker() {
+    let thread_pool = Arc::new(ClosureThread::<u64>::builder().threads(1).build());
+    let inner = thread_pool.clone();
+    let waiting = thread_pool.spawn(move || {
+        let items = vec![1u64; 500];
+        let total = AtomicU64::new(0);
+        inner.scope(|s| {
+            for chunk in items.chunks(50) {
+                let total = &total;
+                s.spawn(move || {
+                    total.fetch_add(chunk.iter().sum(), Ordering::Relaxed);
+                    0
+                });
+            }
+        });
+        total.load(Ordering::Relaxed)
+    });
+    assert_eq!(waiting.collect(), Ok(500));
+}
@@ -0,0 +1,101 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{sleep, yield_now},
+    time::Duration,
+};
+
+use simboli_thread::{ClosureTask, ClosureThread, TaskGraph, TryCollectError, Waiting};
+
+#[test]
+fn collect_waits_for_continuations_reading_the_output() {
+    let thread_pool = ClosureThread::<Vec<u64>>::builder().threads(2).build();
+    for _ in 0..100 {
+        let parent = thread_pool.spawn(|| vec![1, 2, 3]);
+        let read = Arc::new(AtomicBool::new(false));
+        let is_read = read.clone();
+        let mapped = parent.map(move |output| {
+            sleep(Duration::from_micros(100));
+            is_read.store(true, Ordering::SeqCst);
+            output.iter().sum::<u64>()
+        });
+
+        assert_eq!(parent.collect(), Ok(vec![1, 2, 3]));
+        assert!(read.load(Ordering::SeqCst));
+        assert_eq!(mapped.collect(), Ok(6));
+    }
+    thread_pool.join();
+}
+
+#[test]
+fn try_collect_gives_the_handle_back_while_shared() {
+    let thread_pool = ClosureThread::<Vec<u64>>::closures();
+    let parent = thread_pool.spawn(|| vec![1]);
+    let gate = Arc::new(AtomicBool::new(false));
+    let is_open = gate.clone();
+    let mapped = parent.map(move |output| {
+        while !is_open.load(Ordering::SeqCst) {
+            yield_now();
+        }
+        output.len()
+    });
+
+    parent.block().unwrap();
+    let parent = match parent.try_collect() {
+        Err(TryCollectError::NotReady(parent)) => parent,
+        _ => panic!("collected while a continuation reads the output"),
+    };
+    gate.store(true, Ordering::SeqCst);
+    assert_eq!(mapped.collect(), Ok(1));
+    assert_eq!(parent.collect(), Ok(vec![1]));
+    thread_pool.join();
+}
+
+#[test]
+fn collect_waits_for_spawn_after_dependents() {
+    let thread_pool = ClosureThread::<Vec<u64>>::closures();
+    let parent = thread_pool.spawn(|| vec![4, 5]);
+    let child = thread_pool.spawn_after(
+        &[&parent],
+        ClosureTask::with_dependencies(|dependencies: &[Waiting<Vec<u64>>]| {
+            sleep(Duration::from_millis(10));
+            dependencies[0].get().unwrap().clone()
+        }),
+    );
+
+    assert_eq!(parent.collect(), Ok(vec![4, 5]));
+    assert_eq!(child.collect(), Ok(vec![4, 5]));
+    thread_pool.join();
+}
+
+#[test]
+fn graph_node_depending_on_a_waiting_reads_it() {
+    let thread_pool = ClosureThread::<Vec<u64>>::closures();
+    let external = thread_pool.spawn(|| vec![7]);
+
+    let mut graph = TaskGraph::new();
+    let node = graph.add_task_with_dependencies(ClosureTask::with_dependencies(
+        |dependencies: &[Waiting<Vec<u64>>]| dependencies[0].get().unwrap().clone(),
+    ));
+    graph.depends_on_waiting(node, &external);
+    let waitings = thread_pool.spawn_graph(graph).unwrap();
+
+    assert_eq!(external.collect(), Ok(vec![7]));
+    assert_eq!(waitings[node.index()].block(), Ok(&vec![7]));
+    thread_pool.join();
+}
+
+#[test]
+fn await_waits_for_continuations_too() {
+    let thread_pool = Arc::new(ClosureThread::<Vec<u64>>::closures());
+    let parent = thread_pool.spawn(|| vec![4]);
+    let mapped = parent.map(|output| {
+        sleep(Duration::from_millis(20));
+        output.len() as u64
+    });
+    let awaited = thread_pool.spawn_future(async move { parent.await.unwrap() });
+    assert_eq!(awaited.collect(), Ok(vec![4]));
+    assert_eq!(mapped.collect(), Ok(1));
+}
@@ -0,0 +1,87 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use simboli_thread::{AnyOutput, AnyThread, ClosureThread, Waiting, WaitingError};
+
+// counts its drops
+struct Tracked(Arc<AtomicUsize>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn typed_after_gets_the_typed_outputs() {
+    let thread_pool = AnyThread::closures();
+    let number: Waiting<i32> = thread_pool.spawn_typed(|| 6);
+    let text: Waiting<String> = thread_pool.spawn_typed(|| "ab".to_string());
+    let doubled = number.map(|number| *number as u64 * 2);
+
+    let joined = thread_pool.spawn_typed_after(
+        (&number, &text, &doubled),
+        |(number, text, doubled): (&i32, &String, &u64)| format!("{}{}{}", number, text, doubled),
+    );
+    assert_eq!(joined.collect(), Ok("6ab12".to_string()));
+    thread_pool.join();
+}
+
+#[test]
+fn typed_after_fails_with_its_dependencies() {
+    let thread_pool = AnyThread::closures();
+    let panicked: Waiting<i32> = thread_pool.spawn_typed(|| panic!("typed panic"));
+    let dependent = thread_pool.spawn_typed_after((&panicked,), |(number,): (&i32,)| *number);
+    assert!(matches!(
+        dependent.block(),
+        Err(WaitingError::DependencyFailed(_))
+    ));
+
+    // a wrong type panics in the task, not in the caller
+    let wrong = thread_pool.spawn_typed(|| 1u8).downcast::<u16>();
+    let dependent = thread_pool.spawn_typed_after((&wrong,), |(number,): (&u16,)| *number);
+    assert!(matches!(dependent.block(), Err(WaitingError::Panicked(_))));
+    thread_pool.join();
+}
+
+#[test]
+fn reading_another_type_does_not_panic() {
+    let thread_pool = AnyThread::closures();
+    let number: Waiting<i32> = thread_pool.spawn_typed(|| 6);
+    number.block().unwrap();
+
+    assert_eq!(number.downcast_ref::<u8>().get(), None);
+    assert!(number.try_downcast_ref::<u8>().is_none());
+    assert_eq!(number.try_downcast_ref::<i32>().unwrap().get(), Some(&6));
+    assert!(number.downcast_ref::<AnyOutput>().get().is_some());
+    thread_pool.join();
+}
+
+#[test]
+fn typed_thread_pool_outputs_are_dropped_once() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let thread_pool = ClosureThread::<Tracked>::closures();
+
+    let counter = drops.clone();
+    let dropped = thread_pool.spawn(move || Tracked(counter));
+    dropped.block().ok();
+    assert!(dropped.downcast_ref::<AnyOutput>().get().is_none());
+    drop(dropped);
+
+    let counter = drops.clone();
+    let erased = thread_pool
+        .spawn(move || Tracked(counter))
+        .downcast::<AnyOutput>();
+    let output = erased.collect().ok().unwrap();
+    assert!(output.is::<Tracked>());
+    drop(output);
+
+    let counter = drops.clone();
+    let collected = thread_pool.spawn(move || Tracked(counter)).collect();
+    drop(collected);
+
+    thread_pool.join();
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+}